@@ -0,0 +1,50 @@
+//! Inputs in memory split in place against inputs read through `io::Read`, for every solver on the
+//! output of `aoc gen`. Both paths share the parsers of the days, which read the bytes of the
+//! lines: what differs is copying the lines out of a reader into a buffer, which matters little
+//! next to parsing, except for part 1 of day 1 whose scan of the bytes does not split lines.
+//!
+//! ```sh
+//! cargo bench --bench bytes
+//! ```
+
+use adventofcode2023::gen;
+use adventofcode2023::registry::{Run, SOLVERS};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 2_000; // about this many lines per input, which day 5 part 2 keeps small
+const SEED: u64 = 1;
+
+fn main() {
+    for s in &SOLVERS {
+        let Some(generated) = gen::generate(s.day, SIZE, SEED) else {
+            continue;
+        };
+        let input = generated.input.as_bytes();
+
+        let (read, t_read) = time(|| s.solve(&mut &input[..]));
+        let (bytes, t_bytes) = time(|| s.solve_bytes(input));
+        assert_eq!(read, bytes, "day {} part {}", s.day, s.part);
+
+        println!(
+            "day {:02} part {} ({:>8} bytes): read {t_read:>9.1?}, bytes {t_bytes:>9.1?} ({:.2}x)",
+            s.day,
+            s.part,
+            input.len(),
+            t_read.as_secs_f64() / t_bytes.as_secs_f64(),
+        );
+    }
+}
+
+// Answer, or error, and minimum time of a few runs, parsing included.
+fn time(f: impl Fn() -> Result<Run, adventofcode2023::Error>) -> (String, Duration) {
+    let mut best = Duration::MAX;
+    let mut result = None;
+    for _ in 0..5 {
+        let begin = Instant::now();
+        let run = black_box(f());
+        best = best.min(begin.elapsed());
+        result = Some(run.map_or_else(|e| e.to_string(), |r| r.answer));
+    }
+    (result.unwrap(), best)
+}
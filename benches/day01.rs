@@ -0,0 +1,69 @@
+//! Day 1 on very long lines: searching each line from both ends takes a time which depends on the
+//! distance to the nearest digit at each end, instead of the length of the line. Splitting the
+//! input into lines still reads every byte, which the timings of the whole solvers include.
+//!
+//! ```sh
+//! cargo bench --bench day01
+//! ```
+
+use adventofcode2023::automaton::Automaton;
+use adventofcode2023::day01::ex2::Vocabulary;
+use adventofcode2023::day01::{ex1, ex2};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const TOTAL_BYTES: usize = 32 << 20; // of the input of the solvers, per line length
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+fn main() {
+    let matcher = Vocabulary::english().matcher();
+    let automaton = forward_only();
+    // Digits and words close to the ends of the lines.
+    for len in [100, 10_000, 1_000_000, 10_000_000] {
+        let line = format!("ab3{}five7cd\n", "x".repeat(len));
+
+        let (both_ends, t_both) = time(|| matcher.calibration(line.as_bytes()));
+        let (whole, t_whole) = time(|| {
+            let mut matches = automaton.find_overlapping(line.as_bytes());
+            let first = matches.next()?;
+            Some(first.value * 10 + matches.last().unwrap_or(first).value)
+        });
+        assert_eq!((both_ends, whole), (Some(37), Some(37)));
+
+        let lines = (TOTAL_BYTES / line.len()).max(1);
+        let input = line.repeat(lines);
+        let (part1, t1) = time(|| ex1::sum_bytes(input.as_bytes()).unwrap());
+        let (part2, t2) = time(|| ex2::sum(&ex1::parse_bytes(input.as_bytes()).unwrap()));
+        assert_eq!((part1, part2), (37 * lines as u64, 37 * lines as u64));
+
+        let per_line = |t: Duration| t / lines as u32;
+        println!(
+            "line length {len:>8}: search from both ends {t_both:>9.1?}, of the whole line \
+             {t_whole:>9.1?}; solvers per line: part 1 {:>9.1?}, part 2 {:>9.1?}",
+            per_line(t1),
+            per_line(t2),
+        );
+    }
+}
+
+// Minimum time of a few runs.
+fn time<T>(f: impl Fn() -> T) -> (T, Duration) {
+    let mut best = Duration::MAX;
+    let mut result = None;
+    for _ in 0..5 {
+        let begin = Instant::now();
+        result = Some(black_box(f()));
+        best = best.min(begin.elapsed());
+    }
+    (result.unwrap(), best)
+}
+
+// Part 2 matcher reading lines forward only, to their end.
+fn forward_only() -> Automaton<u64> {
+    let digits = (0..10).map(|d| (vec![b'0' + d as u8], d));
+    let words = (1..).zip(WORDS).map(|(n, w)| (w.as_bytes().to_vec(), n));
+    Automaton::new(digits.chain(words))
+}
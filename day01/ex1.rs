@@ -1,41 +1,260 @@
-use std::fs::File;
+use adventofcode2023::input::Source;
+use adventofcode2023::lines::{self, LineSource, Lines};
+use adventofcode2023::Error;
+use std::env;
+use std::fmt;
 use std::io;
-use std::io::{BufRead, BufReader};
+use std::process::ExitCode;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day01/input")?;
-    println!("{}", sum(f));
+// Usage: day01-1 [<input>] [--explain]
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let (flags, positional): (Vec<_>, Vec<_>) = args.iter().partition(|a| *a == "--explain");
+    let f = Source::resolve(1, positional.first().map(|a| a.as_str())).open()?;
+    if flags.is_empty() {
+        println!("{}", sum(&parse(f)?));
+    } else {
+        explain(f, &mut io::stdout().lock())?;
+    }
     Ok(())
 }
 
-fn sum<R: io::Read>(r: R) -> u64 {
-    let r = BufReader::new(r);
-    r.lines().fold(0, |acc, l| {
-        let line = l.unwrap();
-        // Possible improvement: interrupt the iteration and iterate again in reverse as soon as we
-        // find the first digit.
-        let mut digits = line.chars().filter_map(|c| {
-            if c.is_ascii_digit() {
-                Some((c as u8 - b'0') as u64)
-            } else {
-                None
-            }
-        });
-        if let Some(first) = digits.next() {
-            acc + first * 10
-                + if let Some(last) = digits.last() {
-                    last
-                } else {
-                    first
-                }
-        } else {
-            acc
+// Lines of the calibration document, which each part reads its own way.
+pub fn parse<R: io::Read>(r: R) -> Result<Vec<String>, Error> {
+    Lines::new(r).parse_each(|_, line| Ok(lines::text(line)?.to_string()))
+}
+
+// Same as `parse`, for an input held in memory.
+pub fn parse_bytes(input: &[u8]) -> Result<Vec<String>, Error> {
+    lines::split(input).parse_each(|_, line| Ok(lines::text(line)?.to_string()))
+}
+
+pub fn sum(lines: &[String]) -> u64 {
+    lines
+        .iter()
+        .map(|line| calibration(line.as_bytes()).unwrap_or(0))
+        .sum()
+}
+
+// Same as `sum` straight from the reader, one line at a time.
+pub fn stream<R: io::Read>(r: R) -> Result<u64, Error> {
+    Lines::new(r).try_fold(0, |acc, (_, line)| {
+        Ok(acc + calibration(lines::text(line)?.as_bytes()).unwrap_or(0))
+    })
+}
+
+// Same as `sum`, scanning the bytes of an input held in memory with SIMD where available. Other
+// inputs are parsed, so that invalid UTF-8 is reported the same way.
+pub fn sum_bytes(input: &[u8]) -> Result<u64, Error> {
+    #[cfg(target_arch = "x86_64")]
+    if let Some(total) = simd::sum(input) {
+        return Ok(total);
+    }
+    Ok(sum(&parse_bytes(input)?))
+}
+
+// First and last digits of the line, if it has any, searched from each end.
+fn calibration(line: &[u8]) -> Option<u64> {
+    let first = line.iter().position(u8::is_ascii_digit)?;
+    let last = line.iter().rposition(u8::is_ascii_digit)?;
+    Some(u64::from(line[first] - b'0') * 10 + u64::from(line[last] - b'0'))
+}
+
+// Scan of the input 16 bytes at a time with SSE2, which every x86_64 processor has: masks of the
+// digits and newlines of a chunk give the first and last digit of the lines it holds, without
+// splitting the input into lines first.
+#[cfg(target_arch = "x86_64")]
+mod simd {
+    use std::arch::x86_64::*;
+
+    // None if the input is not ASCII.
+    pub fn sum(input: &[u8]) -> Option<u64> {
+        let mut line = None;
+        let mut total = 0;
+        let mut chunks = input.chunks_exact(16);
+        for chunk in &mut chunks {
+            scan(chunk.try_into().unwrap(), &mut line, &mut total)?;
+        }
+        // Padding with zeros, which are neither digits nor newlines.
+        let mut last = [0; 16];
+        last[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+        scan(&last, &mut line, &mut total)?;
+        Some(total + line.map_or(0, value))
+    }
+
+    // Adds the lines ending in the chunk to the total. `line` holds the first and last digits seen
+    // so far in the current line.
+    fn scan(chunk: &[u8; 16], line: &mut Option<(u8, u8)>, total: &mut u64) -> Option<()> {
+        // SAFETY: SSE2 is enabled on x86_64, and the unaligned load reads the 16 bytes of the chunk.
+        let (mut digits, mut newlines, high) = unsafe {
+            let v = _mm_loadu_si128(chunk.as_ptr().cast());
+            // b'0'..=b'9' are shifted to the lowest signed bytes, -128..=-119.
+            let shifted = _mm_add_epi8(v, _mm_set1_epi8(0x50));
+            let digits = _mm_cmplt_epi8(shifted, _mm_set1_epi8(-118));
+            let newlines = _mm_cmpeq_epi8(v, _mm_set1_epi8(b'\n' as i8));
+            (
+                _mm_movemask_epi8(digits) as u32,
+                _mm_movemask_epi8(newlines) as u32,
+                _mm_movemask_epi8(v),
+            )
+        };
+        if high != 0 {
+            return None;
         }
+        while newlines != 0 {
+            let k = newlines.trailing_zeros();
+            extend(line, chunk, digits & ((1 << k) - 1));
+            *total += line.take().map_or(0, value);
+            digits &= !((2 << k) - 1);
+            newlines &= newlines - 1;
+        }
+        extend(line, chunk, digits);
+        Some(())
+    }
+
+    // Adds the digits of the chunk flagged in the mask to the line.
+    fn extend(line: &mut Option<(u8, u8)>, chunk: &[u8; 16], mask: u32) {
+        if mask == 0 {
+            return;
+        }
+        let last = chunk[31 - mask.leading_zeros() as usize];
+        let first = line.map_or(chunk[mask.trailing_zeros() as usize], |(first, _)| first);
+        *line = Some((first, last));
+    }
+
+    fn value((first, last): (u8, u8)) -> u64 {
+        u64::from(first - b'0') * 10 + u64::from(last - b'0')
+    }
+}
+
+/// Writes how every line contributes to the sum, which is returned: its digits, the first and last
+/// ones, and the running total. Lines without digit are flagged with "!!".
+pub fn explain<R: io::Read>(r: R, out: &mut impl io::Write) -> Result<u64, Error> {
+    Lines::new(r).try_fold(0, |total, (i, line)| {
+        let line = lines::text(line)?;
+        let tokens = line
+            .char_indices()
+            .filter(|(_, c)| c.is_ascii_digit())
+            .map(|(j, c)| Token {
+                start: j,
+                text: &line[j..j + 1],
+                value: u64::from(c as u8 - b'0'),
+                spelled: false,
+            })
+            .collect::<Vec<_>>();
+        let ends = tokens.first().zip(tokens.last());
+        let ends = ends.map(|(&first, &last)| (first, last, first.value * 10 + last.value));
+        Ok(write_explanation(out, i, line, &tokens, ends, total)?)
     })
 }
 
+/// Number found in a line, spelled out or in digits.
+#[derive(Clone, Copy)]
+pub struct Token<'a> {
+    pub start: usize, // byte offset in the line
+    pub text: &'a str,
+    pub value: u64,
+    pub spelled: bool,
+}
+
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = if self.spelled { "spelled" } else { "digit" };
+        write!(f, "{:?}@{} ({kind} {})", self.text, self.start, self.value)
+    }
+}
+
+/// Writes the explanation of line `i`: its tokens and, if it has any, the first and last ones with
+/// the calibration value they make. Returns the total with this value.
+pub fn write_explanation(
+    out: &mut impl io::Write,
+    i: usize,
+    line: &str,
+    tokens: &[Token],
+    ends: Option<(Token, Token, u64)>,
+    total: u64,
+) -> io::Result<u64> {
+    let Some((first, last, value)) = ends else {
+        writeln!(out, "!! line {}: {line:?}", i + 1)?;
+        writeln!(out, "     no digit, adds nothing; total {total}")?;
+        return Ok(total);
+    };
+    let total = total + value;
+    let tokens = tokens.iter().map(Token::to_string).collect::<Vec<_>>();
+    writeln!(out, "   line {}: {line:?}", i + 1)?;
+    writeln!(out, "     tokens: {}", tokens.join(", "))?;
+    writeln!(
+        out,
+        "     first {first}, last {last}: {value}; total {total}"
+    )?;
+    Ok(total)
+}
+
+#[test]
+fn larger_than_puzzle_input() {
+    let input = format!("a1{}2b\n", "x3y".repeat(10_000)).repeat(1200);
+    assert_eq!(sum(&parse(input.as_bytes()).unwrap()), 1200 * 12);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    // Lines with digits among letters, whose calibration values are known.
+    #[test]
+    fn round_trip(lines in proptest::collection::vec(
+        ("[a-z]{0,4}", 1..10u64, "[a-z]{0,4}", proptest::option::of(1..10u64), "[a-z]{0,4}"),
+        0..20,
+    )) {
+        let mut input = String::new();
+        let mut expected = 0;
+        for (a, first, b, last, c) in lines {
+            match last {
+                Some(last) => input += &format!("{a}{first}{b}{last}{c}\n"),
+                None => input += &format!("{a}{first}{b}{c}\n"),
+            }
+            expected += first * 10 + last.unwrap_or(first);
+        }
+        proptest::prop_assert_eq!(sum(&parse(input.as_bytes()).unwrap()), expected);
+    }
+
+    // Whatever path it takes, parsing bytes finds the same sum as reading lines.
+    #[test]
+    fn same_sum_from_bytes(input in "[0-9a-z\r\n]{0,100}", text in "\\PC{0,3}") {
+        let input = input + &text;
+        let from_bytes = sum_bytes(input.as_bytes()).map_err(|e| e.to_string());
+        let read = parse(input.as_bytes()).map(|lines| sum(&lines)).map_err(|e| e.to_string());
+        proptest::prop_assert_eq!(from_bytes, read);
+    }
+}
+
 #[test]
-fn mixed_patterns() {
-    let input = "x2xx1\n2x\nxx11x\n".as_bytes();
-    assert_eq!(sum(input), 54);
+fn explanation() {
+    let input = "1abc2\nnone\ntreb7uchet\n".as_bytes();
+    let mut out = Vec::new();
+    assert_eq!(explain(input, &mut out).unwrap(), stream(input).unwrap());
+    let out = String::from_utf8(out).unwrap();
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[..5],
+        [
+            "   line 1: \"1abc2\"",
+            "     tokens: \"1\"@0 (digit 1), \"2\"@4 (digit 2)",
+            "     first \"1\"@0 (digit 1), last \"2\"@4 (digit 2): 12; total 12",
+            "!! line 2: \"none\"",
+            "     no digit, adds nothing; total 12",
+        ]
+    );
+    assert_eq!(
+        lines[7],
+        "     first \"7\"@4 (digit 7), last \"7\"@4 (digit 7): 77; total 89"
+    );
 }
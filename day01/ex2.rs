@@ -1,113 +1,274 @@
-use std::fs::File;
+use adventofcode2023::automaton::Automaton;
+use adventofcode2023::day01::ex1::{self, Token};
+use adventofcode2023::input::Source;
+use adventofcode2023::lines::{self, Lines};
+use adventofcode2023::{toml, Error};
+use std::env;
+use std::fs;
 use std::io;
-use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::ExitCode;
 
-const MAX_DIGITS_PER_LINE: usize = 10;
+// Spelled out digits of the built-in vocabularies, from 1 to 9.
+const ENGLISH: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const FRENCH: [&str; 9] = [
+    "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+];
+const GERMAN: [&str; 9] = [
+    "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+];
+const SPANISH: [&str; 9] = [
+    "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+];
 
-fn main() -> io::Result<()> {
-    let f = File::open("day01/input")?;
-    println!("{}", sum(f));
-    Ok(())
+// Usage: day01-2 [<input> [en|fr|de|es|<vocabulary file>]] [--explain]
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn sum<R: io::Read>(r: R) -> u64 {
-    let r = BufReader::new(r);
-    let mut digits = Vec::with_capacity(MAX_DIGITS_PER_LINE);
-    r.lines().fold(0, |acc, l| {
-        let line = l.unwrap();
-        let mut iter = line.chars().enumerate();
-        digits.clear();
-        // Possible improvement: interrupt the iteration and iterate again in reverse as soon as we
-        // find the first digit.
-        while let Some((i, c)) = iter.next() {
-            if c.is_ascii_digit() {
-                digits.push((c as u8 - b'0') as u64);
-                continue;
+fn run() -> Result<(), Error> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let (flags, positional): (Vec<_>, Vec<_>) = args.iter().partition(|a| *a == "--explain");
+    let f = Source::resolve(1, positional.first().map(|a| a.as_str())).open()?;
+    let vocabulary = match positional.get(1) {
+        Some(v) => match Vocabulary::resolve(v) {
+            Ok(vocabulary) => vocabulary,
+            Err(e) => {
+                eprintln!("cannot load vocabulary {v}: {e}");
+                std::process::exit(1);
             }
-            // The set of possible words is small (lowercase single digit numbers in English), so
-            // we can reasonably enumerate all possible substrings based on the currently visited
-            // character, and swallow the rest of the word if we find a match.
-            let remain_chars = line[i..].len();
-            if remain_chars.lt(&3) {
-                continue;
+        },
+        None => Vocabulary::english(),
+    };
+    if flags.is_empty() {
+        println!("{}", sum_with(&ex1::parse(f)?, &vocabulary));
+    } else {
+        explain(f, &vocabulary, &mut io::stdout().lock())?;
+    }
+    Ok(())
+}
+
+/// Spelled out numbers recognized besides the digits, with their values. Words may overlap, as in
+/// "eightwo", and contain one another, as in "seventeen". A vocabulary file lists them in TOML:
+///
+/// ```toml
+/// zero = 0
+/// one = 1
+/// twenty = 20
+/// "vingt et un" = 21
+/// ```
+pub struct Vocabulary(Vec<(String, u64)>);
+
+impl Vocabulary {
+    /// Words of the puzzle, "one" to "nine".
+    pub fn english() -> Self {
+        Self::from_words(ENGLISH)
+    }
+
+    /// Built-in vocabulary of the given language: en, fr, de or es.
+    pub fn builtin(lang: &str) -> Option<Self> {
+        match lang {
+            "en" => Some(Self::from_words(ENGLISH)),
+            "fr" => Some(Self::from_words(FRENCH)),
+            "de" => Some(Self::from_words(GERMAN)),
+            "es" => Some(Self::from_words(SPANISH)),
+            _ => None,
+        }
+    }
+
+    /// Built-in vocabulary of the given name, or else the vocabulary file at this path.
+    pub fn resolve(name_or_path: &str) -> io::Result<Self> {
+        match Self::builtin(name_or_path) {
+            Some(v) => Ok(v),
+            None => Self::load(Path::new(name_or_path)),
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn parse(s: &str) -> Result<Self, toml::Error> {
+        let mut words = Vec::new();
+        for t in toml::parse(s)? {
+            let err = |expected| toml::Error {
+                line: t.line,
+                expected,
+            };
+            if !t.name.is_empty() {
+                return Err(err("only word = number entries"));
             }
-            if let Some(n) = match c {
-                'o' => {
-                    if "one" == &line[i..i + 3] {
-                        iter.next(); // don't swallow 'e', it could be the start of "eight"
-                        Some(1)
-                    } else {
-                        None
+            for (word, value) in t.entries {
+                match value {
+                    toml::Value::Integer(n) if n >= 0 && !word.is_empty() => {
+                        words.push((word, n as u64))
                     }
+                    _ => return Err(err("word = non-negative integer")),
                 }
-                't' => {
-                    if remain_chars.ge(&5) && "three" == &line[i..i + 5] {
-                        iter.nth(2); // don't swallow 'e', it could be the start of "eight"
-                        Some(3)
-                    } else if "two" == &line[i..i + 3] {
-                        iter.next(); // don't swallow 'o', it could be the start of "one"
-                        Some(2)
-                    } else {
-                        None
-                    }
-                }
-                'f' => {
-                    if remain_chars.ge(&4) {
-                        if "four" == &line[i..i + 4] {
-                            iter.nth(2);
-                            Some(4)
-                        } else if "five" == &line[i..i + 4] {
-                            iter.nth(1); // don't swallow 'e', it could be the start of "eight"
-                            Some(5)
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                }
-                's' => {
-                    if remain_chars.ge(&5) && "seven" == &line[i..i + 5] {
-                        iter.nth(2); // don't swallow 'n', it could be the start of "nine"
-                        Some(7)
-                    } else if "six" == &line[i..i + 3] {
-                        iter.nth(1);
-                        Some(6)
-                    } else {
-                        None
-                    }
-                }
-                'e' => {
-                    if remain_chars.ge(&5) && "eight" == &line[i..i + 5] {
-                        iter.nth(2); // don't swallow 't', it could be the start of {"two","three"}
-                        Some(8)
-                    } else {
-                        None
-                    }
-                }
-                'n' => {
-                    if remain_chars.ge(&4) && "nine" == &line[i..i + 4] {
-                        iter.nth(1); // don't swallow 'e', it could be the start of "eight"
-                        Some(9)
-                    } else {
-                        None
-                    }
-                }
-                _ => None,
-            } {
-                digits.push(n)
-            };
+            }
         }
-        if let Some(first) = digits.first() {
-            acc + first * 10 + digits.last().unwrap()
-        } else {
-            acc
+        Ok(Vocabulary(words))
+    }
+
+    fn from_words(words: [&str; 9]) -> Self {
+        Vocabulary((1..).zip(words).map(|(n, w)| (w.to_string(), n)).collect())
+    }
+
+    pub fn matcher(&self) -> Matcher {
+        let digits = (0..10).map(|d| (vec![b'0' + d as u8], d));
+        let words = self.0.iter().map(|(w, n)| (w.as_bytes().to_vec(), *n));
+        let table = digits.chain(words).collect::<Vec<_>>();
+        let reversed = table
+            .iter()
+            .map(|(p, n)| (p.iter().rev().copied().collect::<Vec<_>>(), *n));
+        Matcher {
+            forward: Automaton::new(table.iter().map(|(p, n)| (p, *n))),
+            backward: Automaton::new(reversed),
         }
+    }
+}
+
+/// Digits and words of a vocabulary, spelled forward and backward so that lines are searched from
+/// both ends.
+pub struct Matcher {
+    forward: Automaton<u64>,
+    backward: Automaton<u64>,
+}
+
+impl Matcher {
+    /// First digit of the number starting first and last digit of the one ending last, if the
+    /// line has any. Among numbers starting or ending at the same byte, the longest is read, so
+    /// that "seventeen" is 17 when the vocabulary has it. Only the ends of the line are read, up
+    /// to these numbers.
+    pub fn calibration(&self, line: &[u8]) -> Option<u64> {
+        let first = self.forward.first_match(line.iter().copied())?.value;
+        let last = self.backward.first_match(line.iter().rev().copied())?.value;
+        Some(leading_digit(first) * 10 + last % 10)
+    }
+
+    // All the numbers of the line, overlapping ones included, and those which `calibration` reads
+    // with its value.
+    fn explain<'a>(&self, line: &'a str) -> (Vec<Token<'a>>, Option<(Token<'a>, Token<'a>, u64)>) {
+        let token = |start: usize, end: usize, pattern: usize, value: u64| Token {
+            start,
+            text: &line[start..end],
+            value,
+            spelled: pattern >= 10, // after the digits in the table
+        };
+        let bytes = line.as_bytes();
+        let tokens = self
+            .forward
+            .find_overlapping(bytes)
+            .map(|m| token(m.start, m.end, m.pattern, m.value))
+            .collect();
+        let first = self.forward.first_match(bytes.iter().copied());
+        let last = self.backward.first_match(bytes.iter().rev().copied());
+        let ends = first.zip(last).map(|(f, l)| {
+            let (first, last) = (
+                token(f.start, f.end, f.pattern, f.value),
+                token(line.len() - l.end, line.len() - l.start, l.pattern, l.value),
+            );
+            (first, last, leading_digit(f.value) * 10 + l.value % 10)
+        });
+        (tokens, ends)
+    }
+}
+
+pub fn sum(lines: &[String]) -> u64 {
+    sum_with(lines, &Vocabulary::english())
+}
+
+pub fn sum_with(lines: &[String], vocabulary: &Vocabulary) -> u64 {
+    let matcher = vocabulary.matcher();
+    lines
+        .iter()
+        .map(|line| matcher.calibration(line.as_bytes()).unwrap_or(0))
+        .sum()
+}
+
+// Same as `sum` straight from the reader, one line at a time.
+pub fn stream<R: io::Read>(r: R) -> Result<u64, Error> {
+    let matcher = Vocabulary::english().matcher();
+    Lines::new(r).try_fold(0, |acc, (_, line)| {
+        Ok(acc
+            + matcher
+                .calibration(lines::text(line)?.as_bytes())
+                .unwrap_or(0))
+    })
+}
+
+/// Same as `ex1::explain`, with the numbers of the vocabulary.
+pub fn explain<R: io::Read>(
+    r: R,
+    vocabulary: &Vocabulary,
+    out: &mut impl io::Write,
+) -> Result<u64, Error> {
+    let matcher = vocabulary.matcher();
+    Lines::new(r).try_fold(0, |total, (i, line)| {
+        let line = lines::text(line)?;
+        let (tokens, ends) = matcher.explain(line);
+        Ok(ex1::write_explanation(out, i, line, &tokens, ends, total)?)
     })
 }
 
+fn leading_digit(mut n: u64) -> u64 {
+    while n >= 10 {
+        n /= 10;
+    }
+    n
+}
+
+#[test]
+fn larger_than_puzzle_input() {
+    // Many digits per line, and more lines than the puzzle input.
+    let input = format!("two{}nine\n", "1eight".repeat(100)).repeat(1200);
+    assert_eq!(sum(&ex1::parse(input.as_bytes()).unwrap()), 1200 * 29);
+}
+
+#[test]
+fn overlapping_words() {
+    let input = "eightwo\nxtwonex\nsevenine3oneight\nnodigit\n".as_bytes();
+    assert_eq!(stream(input).unwrap(), 82 + 21 + 78);
+}
+
+#[test]
+fn vocabularies() {
+    let fr = Vocabulary::builtin("fr").unwrap();
+    let lines = ex1::parse("troisept\nxneufx\n".as_bytes()).unwrap();
+    assert_eq!(sum_with(&lines, &fr), 37 + 99);
+    let de = Vocabulary::builtin("de").unwrap();
+    let lines = ex1::parse_bytes("fünfzweins".as_bytes()).unwrap();
+    assert_eq!(sum_with(&lines, &de), 51);
+
+    let custom = Vocabulary::parse("zero = 0\ntwenty = 20\nseventeen = 17\nseven = 7\n").unwrap();
+    let lines = ex1::parse("twentyzero\nseventeen\nxsevenx\n".as_bytes()).unwrap();
+    assert_eq!(sum_with(&lines, &custom), 20 + 17 + 77);
+    assert!(Vocabulary::parse("one = -1\n").is_err());
+}
+
 #[test]
-fn mixed_patterns() {
-    let input = "x2xxoneeight\n2xtwo\nonexx21x\n".as_bytes();
-    assert_eq!(sum(input), 61);
+fn explanation() {
+    let input = "eightwo\nxyz\n".as_bytes();
+    let mut out = Vec::new();
+    let total = explain(input, &Vocabulary::english(), &mut out).unwrap();
+    assert_eq!(total, stream(input).unwrap());
+    let out = String::from_utf8(out).unwrap();
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[1..4],
+        [
+            "     tokens: \"eight\"@0 (spelled 8), \"two\"@4 (spelled 2)",
+            "     first \"eight\"@0 (spelled 8), last \"two\"@4 (spelled 2): 82; total 82",
+            "!! line 2: \"xyz\"",
+        ]
+    );
 }
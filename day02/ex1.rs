@@ -1,86 +1,145 @@
-use std::fs::File;
+use adventofcode2023::lines::{self, LineSource, Lines};
+use adventofcode2023::{input, Error, ParseError};
 use std::io;
-use std::io::{BufRead, BufReader};
+use std::process::ExitCode;
 
-const MAX_REDS: u8 = 12;
-const MAX_GREENS: u8 = 13;
-const MAX_BLUES: u8 = 14;
+const DAY: u8 = 2;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day02/input")?;
-    println!("{}", sum(f));
+const MAX_REDS: u32 = 12;
+const MAX_GREENS: u32 = 13;
+const MAX_BLUES: u32 = 14;
+
+const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", sum(&parse(f)?)?);
     Ok(())
 }
 
-fn sum<R: io::Read>(r: R) -> u32 {
-    let r = BufReader::new(r);
-    let mut cubes = String::with_capacity(2);
-    r.lines().enumerate().fold(0, |acc, (i, l)| {
-        let line = l.unwrap();
-        let mut iter = line.chars();
-
-        // swallow "Game <id>: "
-        iter.nth(
-            "Game :".len()
-                + if i + 1 < 10 {
-                    1
-                } else if i + 1 < 100 {
-                    2
-                } else {
-                    3
-                },
-        );
-
-        cubes.clear();
-        while let Some(c) = iter.next() {
-            match c {
-                '0'..='9' => {
-                    cubes.push(c);
-                }
-                ' ' => {
-                    match iter.next().unwrap() {
-                        'r' => {
-                            if cubes.parse::<u8>().unwrap() > MAX_REDS {
-                                return acc;
-                            }
-                            cubes.clear();
-                            iter.nth("red".len() - 2);
-                        }
-                        'g' => {
-                            if cubes.parse::<u8>().unwrap() > MAX_GREENS {
-                                return acc;
-                            }
-                            cubes.clear();
-                            iter.nth("green".len() - 2);
-                        }
-                        'b' => {
-                            if cubes.parse::<u8>().unwrap() > MAX_BLUES {
-                                return acc;
-                            }
-                            cubes.clear();
-                            iter.nth("blue".len() - 2);
-                        }
-                        _ => (),
-                    };
-                }
-                ',' | ';' => {
-                    iter.next();
-                }
-                _ => (),
-            }
+/// Game and the most cubes of each color drawn at once: red, green and blue.
+#[derive(Debug)]
+pub struct Game {
+    pub id: u32,
+    pub max: [u32; 3],
+}
+
+pub fn parse<R: io::Read>(r: R) -> Result<Vec<Game>, Error> {
+    Lines::new(r).parse_each(|i, line| Ok(parse_game(i, line)?))
+}
+
+// Same as `parse`, for an input held in memory.
+pub fn parse_bytes(input: &[u8]) -> Result<Vec<Game>, Error> {
+    lines::split(input).parse_each(|i, line| Ok(parse_game(i, line)?))
+}
+
+pub fn sum(games: &[Game]) -> Result<u32, Error> {
+    games.iter().try_fold(0u32, add_id)
+}
+
+// Same as `sum` straight from the reader, one game at a time.
+pub fn stream<R: io::Read>(r: R) -> Result<u32, Error> {
+    Lines::new(r).try_fold(0u32, |acc, (i, line)| add_id(acc, &parse_game(i, line)?))
+}
+
+// Adds the id of the game to `acc` if the game is possible.
+fn add_id(acc: u32, game: &Game) -> Result<u32, Error> {
+    if game.max[0] > MAX_REDS || game.max[1] > MAX_GREENS || game.max[2] > MAX_BLUES {
+        return Ok(acc);
+    }
+    acc.checked_add(game.id)
+        .ok_or(Error::NoAnswer("the sum overflows u32"))
+}
+
+/// Parses game `line`.
+pub fn parse_game(i: usize, line: &[u8]) -> Result<Game, ParseError> {
+    // swallow "Game <id>: "
+    let header = line.strip_prefix(b"Game ");
+    let Some((id, rest)) = header.and_then(|l| lines::split_once(l, b": ")) else {
+        let found = line.split(|&b| b == b' ').next().unwrap_or_default();
+        let found = String::from_utf8_lossy(found);
+        return Err(ParseError::new(DAY, i, 0, found, "\"Game <id>: \""));
+    };
+    let Some(id) = lines::number(id) else {
+        let found = String::from_utf8_lossy(id);
+        return Err(ParseError::new(DAY, i, "Game ".len(), found, "game id"));
+    };
+
+    let mut max = [0; 3];
+    let mut j = line.len() - rest.len();
+    while j < line.len() {
+        // "<cubes> <color>"
+        let digits = line[j..].iter().take_while(|b| b.is_ascii_digit()).count();
+        let end = j + digits;
+        match line.get(end) {
+            Some(b' ') => (),
+            None => return Err(ParseError::at(DAY, i, line, end, "' '")),
+            Some(_) => return Err(ParseError::at(DAY, i, line, end, "digit or ' '")),
         }
+        let cubes = &line[j..end];
+        let Some(n) = lines::number::<u32>(cubes) else {
+            let expected = "number of cubes";
+            return Err(ParseError::token(DAY, i, line, end, cubes, expected));
+        };
+        let rest = &line[end + 1..];
+        let Some((color, name)) = COLORS
+            .iter()
+            .enumerate()
+            .find(|(_, name)| rest.starts_with(name.as_bytes()))
+        else {
+            return Err(ParseError::at(DAY, i, line, end + 1, "color"));
+        };
+        max[color] = max[color].max(n);
+        j = end + 1 + name.len();
 
-        acc + (i + 1) as u32
-    })
+        // then ", " or "; " before the next cubes, if any
+        match line.get(j) {
+            None => (),
+            Some(b',' | b';') if line.get(j + 1) == Some(&b' ') => j += 2,
+            Some(b',' | b';') => return Err(ParseError::at(DAY, i, line, j + 1, "' '")),
+            Some(_) => return Err(ParseError::at(DAY, i, line, j, "',' or ';'")),
+        }
+    }
+    Ok(Game { id, max })
 }
 
 #[test]
-fn basic() {
+fn unknown_color() {
     let input = "\
-        Game 1: 10 blue; 99 blue\n\
-        Game 2: 10 blue, 1 red\n\
-        Game 3: 1 blue, 10 red, 99 green\n\
+        Game 1: 10 blue; 9 blue\n\
+        Game 2: 10 blue, 1 pink\n\
         "
     .as_bytes();
-    assert_eq!(sum(input), 2)
+    match parse(input) {
+        Err(Error::Parse(e)) => assert_eq!(e, ParseError::new(DAY, 1, 19, "p", "color")),
+        r => panic!("unexpected result {r:?}"),
+    }
+    let error = |line: &str| parse_game(0, line.as_bytes()).unwrap_err();
+    assert_eq!(
+        error("Game 1: 3 rXX,X4 gABCD"),
+        ParseError::new(DAY, 0, 10, "r", "color")
+    );
+    assert_eq!(
+        error("Game 1: 3 red,4 green"),
+        ParseError::new(DAY, 0, 14, "4", "' '")
+    );
+    assert_eq!(error("Game 1: 3"), ParseError::new(DAY, 0, 9, "", "' '"));
+}
+
+#[test]
+fn larger_than_puzzle_input() {
+    let input = (1..=1200)
+        .map(|id| format!("Game {id}: 1 red, 2 green; 3 blue\n"))
+        .collect::<String>();
+    assert_eq!(stream(input.as_bytes()).unwrap(), 720600);
 }
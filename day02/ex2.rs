@@ -1,94 +1,81 @@
-use std::fs::File;
+use adventofcode2023::day02::ex1::{self, Game};
+use adventofcode2023::lines::Lines;
+use adventofcode2023::{input, Error};
 use std::io;
-use std::io::{BufRead, BufReader};
+use std::process::ExitCode;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day02/input")?;
-    println!("{}", sum(f));
-    Ok(())
-}
-
-fn sum<R: io::Read>(r: R) -> u32 {
-    let r = BufReader::new(r);
-
-    let mut cubes = String::with_capacity(2);
-    let mut min_red = 0;
-    let mut min_green = 0;
-    let mut min_blue = 0;
+const DAY: u8 = 2;
 
-    r.lines().enumerate().fold(0, |acc, (i, l)| {
-        let line = l.unwrap();
-        let mut iter = line.chars();
-
-        // swallow "Game <id>: "
-        iter.nth(
-            "Game :".len()
-                + if i + 1 < 10 {
-                    1
-                } else if i + 1 < 100 {
-                    2
-                } else {
-                    3
-                },
-        );
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
 
-        cubes.clear();
-        min_red = 0;
-        min_green = 0;
-        min_blue = 0;
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", sum(&ex1::parse(f)?)?);
+    Ok(())
+}
 
-        while let Some(c) = iter.next() {
-            match c {
-                '0'..='9' => {
-                    cubes.push(c);
-                }
-                ' ' => {
-                    match iter.next().unwrap() {
-                        'r' => {
-                            let n = cubes.parse::<u32>().unwrap();
-                            if n > min_red {
-                                min_red = n;
-                            }
-                            cubes.clear();
-                            iter.nth("red".len() - 2);
-                        }
-                        'g' => {
-                            let n = cubes.parse::<u32>().unwrap();
-                            if n > min_green {
-                                min_green = n;
-                            }
-                            cubes.clear();
-                            iter.nth("green".len() - 2);
-                        }
-                        'b' => {
-                            let n = cubes.parse::<u32>().unwrap();
-                            if n > min_blue {
-                                min_blue = n;
-                            }
-                            cubes.clear();
-                            iter.nth("blue".len() - 2);
-                        }
-                        _ => (),
-                    };
-                }
-                ',' | ';' => {
-                    iter.next();
-                }
-                _ => (),
-            }
-        }
+pub fn sum(games: &[Game]) -> Result<u32, Error> {
+    games.iter().try_fold(0u32, add_power)
+}
 
-        acc + (min_red * min_green * min_blue)
+// Same as `sum` straight from the reader, one game at a time.
+pub fn stream<R: io::Read>(r: R) -> Result<u32, Error> {
+    Lines::new(r).try_fold(0u32, |acc, (i, line)| {
+        add_power(acc, &ex1::parse_game(i, line)?)
     })
 }
 
+// Adds the power of the minimum set of cubes of the game to `acc`.
+fn add_power(acc: u32, game: &Game) -> Result<u32, Error> {
+    let [red, green, blue] = game.max;
+    red.checked_mul(green)
+        .and_then(|p| p.checked_mul(blue))
+        .and_then(|power| acc.checked_add(power))
+        .ok_or(Error::NoAnswer("the sum overflows u32"))
+}
+
 #[test]
-fn zero_no_green() {
-    let input = "\
-        Game 1: 10 blue; 1 blue\n\
-        Game 2: 10 blue, 1 red, 2 green\n\
-        Game 3: 1 blue, 2 red, 3 green\n\
-        "
-    .as_bytes();
-    assert_eq!(sum(input), 26)
+fn larger_than_puzzle_input() {
+    let input = (1..=1200)
+        .map(|id| format!("Game {id}: 1 red, 2 green; 3 blue\n"))
+        .collect::<String>();
+    assert_eq!(stream(input.as_bytes()).unwrap(), 7200);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    // Games written from their draws of red, green and blue cubes, whose minimum set is known.
+    #[test]
+    fn round_trip(games in proptest::collection::vec(
+        proptest::collection::vec(
+            proptest::strategy::Strategy::prop_filter(
+                [0..20u32, 0..20u32, 0..20u32],
+                "at least one cube is drawn",
+                |d| d.iter().any(|&n| n > 0),
+            ),
+            1..5,
+        ),
+        0..10,
+    )) {
+        let mut input = String::new();
+        let mut expected = 0;
+        for (id, draws) in games.iter().enumerate() {
+            let draws = draws.iter().map(|draw| {
+                let colors = draw.iter().zip(["red", "green", "blue"]).filter(|(&n, _)| n > 0);
+                colors.map(|(n, c)| format!("{n} {c}")).collect::<Vec<_>>().join(", ")
+            });
+            input += &format!("Game {}: {}\n", id + 1, draws.collect::<Vec<_>>().join("; "));
+            let min = |c: usize| games[id].iter().map(|d| d[c]).max().unwrap_or(0);
+            expected += min(0) * min(1) * min(2);
+        }
+        proptest::prop_assert_eq!(sum(&ex1::parse(input.as_bytes()).unwrap()).unwrap(), expected);
+    }
 }
@@ -1,118 +1,206 @@
-use std::collections::HashSet;
-use std::fs::File;
+use adventofcode2023::lines::{self, LineSource, Lines};
+use adventofcode2023::{input, Error, ParseError};
 use std::io;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
+use std::process::ExitCode;
 
-const NUM_LINES: usize = 140;
+const DAY: u8 = 3;
+
+// Capacity hints, from the shape of the puzzle input.
 const LINE_LENGTH: usize = 140;
-const MAX_NUM_LEN: usize = 3;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day03/input")?;
-    let begin = Instant::now();
-    println!("{}", sum(f));
-    println!("took: {:?}", Instant::now() - begin);
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", sum(&parse(f)?)?);
     Ok(())
 }
 
-type Symbols = HashSet<i16 /*char idx*/>;
-type Numbers = Vec<((usize /*start idx*/, usize /*end idx*/), String)>;
-
-fn sum<R: io::Read>(r: R) -> u32 {
-    let r = BufReader::new(r);
-    let mut iter = r.lines();
-
-    let mut cur_num_start = usize::MAX;
-    let mut cur_num_str = String::with_capacity(MAX_NUM_LEN);
-
-    let symbols_numbers: [(Symbols, Numbers); NUM_LINES] = core::array::from_fn(|_| {
-        match iter.next() {
-            None => (HashSet::new(), Vec::new()), // tests cannot realistically be NUM_LINES long
-            Some(line) => match line {
-                Ok(line) => {
-                    // In the worst case, every character is a symbol
-                    let mut symbols = HashSet::with_capacity(LINE_LENGTH);
-                    // In the worst case, only single digit numbers separated by symbols
-                    let mut numbers = Vec::with_capacity(LINE_LENGTH / 2);
-
-                    for (i, b) in line.bytes().enumerate() {
-                        match b {
-                            b'0'..=b'9' => {
-                                if cur_num_str.is_empty() {
-                                    cur_num_start = i;
-                                }
-                                cur_num_str.push(char::from(b));
-                            }
-                            b'!'..=b'-' | b'/' | b':'..=b'@' => {
-                                symbols.insert(i as i16);
-                                if !cur_num_str.is_empty() {
-                                    numbers.push(((cur_num_start, i - 1), cur_num_str.clone()));
-                                    cur_num_str.clear();
-                                }
-                            }
-                            b'.' => {
-                                if !cur_num_str.is_empty() {
-                                    numbers.push(((cur_num_start, i - 1), cur_num_str.clone()));
-                                    cur_num_str.clear();
-                                }
-                            }
-                            _ => panic!("unexpected char"),
-                        };
-                    }
-
-                    if !cur_num_str.is_empty() {
-                        numbers.push(((cur_num_start, line.len() - 1), cur_num_str.clone()));
-                        cur_num_str.clear();
-                    }
-
-                    (symbols, numbers)
-                }
-                Err(e) => panic!("{e}"),
-            },
+/// Symbols of a line of the schematic, and the numbers between them, in order.
+#[derive(Debug)]
+pub struct Row {
+    pub symbols: Vec<(usize /*char idx*/, u8)>,
+    pub numbers: Vec<((usize /*start idx*/, usize /*end idx*/), u32)>,
+}
+
+impl Row {
+    // Whether the row has a symbol between the indexes `from` and `to` included.
+    fn has_symbol(&self, from: usize, to: usize) -> bool {
+        let k = self.symbols.partition_point(|&(j, _)| j < from);
+        self.symbols.get(k).is_some_and(|&(j, _)| j <= to)
+    }
+}
+
+pub fn parse<R: io::Read>(r: R) -> Result<Vec<Row>, Error> {
+    Lines::new(r).parse_each(|l, line| Ok(parse_line(l, line)?))
+}
+
+// Same as `parse`, for an input held in memory.
+pub fn parse_bytes(input: &[u8]) -> Result<Vec<Row>, Error> {
+    lines::split(input).parse_each(|l, line| Ok(parse_line(l, line)?))
+}
+
+pub fn sum(rows: &[Row]) -> Result<u32, Error> {
+    sum_rows(rows, sum_line)
+}
+
+// Same as `sum` straight from the reader, three lines at a time.
+pub fn stream<R: io::Read>(r: R) -> Result<u32, Error> {
+    stream_rows(r, sum_line)
+}
+
+/// Sum of `sum_line` over the rows, each given with the previous and next ones.
+pub fn sum_rows(
+    rows: &[Row],
+    mut sum_line: impl FnMut(Option<&Row>, &Row, Option<&Row>) -> Option<u32>,
+) -> Result<u32, Error> {
+    (0..rows.len()).try_fold(0u32, |acc, l| {
+        let prev = l.checked_sub(1).map(|l| &rows[l]);
+        sum_line(prev, &rows[l], rows.get(l + 1))
+            .and_then(|s| acc.checked_add(s))
+            .ok_or(Error::NoAnswer("the sum overflows u32"))
+    })
+}
+
+/// Same as `sum_rows` straight from the reader: a line is summed once the next one is parsed, so
+/// that only three lines are kept in memory.
+pub fn stream_rows<R: io::Read>(
+    r: R,
+    mut sum_line: impl FnMut(Option<&Row>, &Row, Option<&Row>) -> Option<u32>,
+) -> Result<u32, Error> {
+    let mut lines = Lines::new(r);
+
+    let mut window: [Option<Row>; 3] = Default::default();
+    let mut acc = 0u32;
+    loop {
+        let next = match lines.next_line() {
+            Some(l) => {
+                let (l, line) = l?;
+                Some(parse_line(l, line)?)
+            }
+            None => None,
+        };
+        let end = next.is_none();
+        window.rotate_left(1);
+        window[2] = next;
+        if let [prev, Some(cur), next] = &window {
+            acc = sum_line(prev.as_ref(), cur, next.as_ref())
+                .and_then(|s| acc.checked_add(s))
+                .ok_or(Error::NoAnswer("the sum overflows u32"))?;
         }
-    });
-
-    symbols_numbers
-        .iter()
-        .enumerate()
-        .fold(0u32, |acc, (l, sn)| {
-            let mut acc_line = 0;
-            for num in &sn.1 {
-                // Cheap optimized case if the number has a symbol directly to its left or right.
-                if sn.0.contains(&(num.0 .0 as i16 - 1)) || sn.0.contains(&(num.0 .1 as i16 + 1)) {
-                    acc_line += num.1.parse::<u32>().unwrap();
-                } else {
-                    // Numbers are at most 3 digits long, so we iterate at most 5 times per number here.
-                    for i in num.0 .0 as i16 - 1..=num.0 .1 as i16 + 1 {
-                        if (l > 0 && symbols_numbers[l - 1].0.contains(&i))
-                            || (l < symbols_numbers.len() - 1
-                                && symbols_numbers[l + 1].0.contains(&i))
-                        {
-                            acc_line += num.1.parse::<u32>().unwrap();
-                            break;
-                        }
-                    }
+        if end {
+            return Ok(acc);
+        }
+    }
+}
+
+// Sum of the numbers of the current line which are adjacent to a symbol, if it fits in u32.
+fn sum_line(prev: Option<&Row>, cur: &Row, next: Option<&Row>) -> Option<u32> {
+    let mut acc_line = 0u32;
+    for &((start, end), n) in &cur.numbers {
+        let (from, to) = (start.saturating_sub(1), end + 1);
+        if [prev, Some(cur), next]
+            .into_iter()
+            .flatten()
+            .any(|row| row.has_symbol(from, to))
+        {
+            acc_line = n.checked_add(acc_line)?;
+        }
+    }
+    Some(acc_line)
+}
+
+fn parse_line(l: usize, line: &[u8]) -> Result<Row, ParseError> {
+    // In the worst case, every character is a symbol
+    let mut symbols = Vec::with_capacity(LINE_LENGTH);
+    // In the worst case, only single digit numbers separated by symbols
+    let mut numbers = Vec::with_capacity(LINE_LENGTH / 2);
+
+    let mut cur_num_start = None;
+    let mut push_num = |start: usize, end: usize| -> Result<(), ParseError> {
+        let digits = &line[start..=end];
+        let n = lines::number(digits).ok_or_else(|| {
+            let found = String::from_utf8_lossy(digits);
+            ParseError::new(DAY, l, start, found, "32-bit number")
+        })?;
+        numbers.push(((start, end), n));
+        Ok(())
+    };
+
+    for (i, &b) in line.iter().enumerate() {
+        match b {
+            b'0'..=b'9' => {
+                cur_num_start.get_or_insert(i);
+            }
+            b'!'..=b'-' | b'/' | b':'..=b'@' => {
+                symbols.push((i, b));
+                if let Some(start) = cur_num_start.take() {
+                    push_num(start, i - 1)?;
+                }
+            }
+            b'.' => {
+                if let Some(start) = cur_num_start.take() {
+                    push_num(start, i - 1)?;
                 }
             }
-            acc + acc_line
-        })
+            _ => return Err(ParseError::at(DAY, l, line, i, "digit, '.' or symbol")),
+        };
+    }
+
+    if let Some(start) = cur_num_start {
+        push_num(start, line.len() - 1)?;
+    }
+
+    Ok(Row { symbols, numbers })
 }
 
 #[test]
-fn sample_input_with_sandwiched_numbers() {
+fn unexpected_char() {
     let input = "\
         467#.114..\n\
-        ...*......\n\
-        ..35..633.\n\
-        ...#..#...\n\
-        617*......\n\
-        .....+..58\n\
-        ..592.....\n\
-        ......755.\n\
-        ...$.*....\n\
-        .664.598..\n\
+        ...*..x...\n\
         "
     .as_bytes();
-    assert_eq!(sum(input), 4361)
+    match parse(input) {
+        Err(Error::Parse(e)) => {
+            assert_eq!(e, ParseError::new(DAY, 1, 6, "x", "digit, '.' or symbol"))
+        }
+        r => panic!("unexpected result {r:?}"),
+    }
+}
+
+#[test]
+fn larger_than_puzzle_input() {
+    // 200 lines of 300 characters, every other one holding 50 gears between two numbers.
+    let input = (0..200)
+        .map(|l| if l % 2 == 0 { "12*12.".repeat(50) } else { ".".repeat(300) } + "\n")
+        .collect::<String>();
+    assert_eq!(stream(input.as_bytes()).unwrap(), 120000);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    // Rows of numbers, each followed by a symbol, between rows of dots.
+    #[test]
+    fn round_trip(rows in proptest::collection::vec(
+        proptest::collection::vec((0..1000u32, proptest::sample::select(&b"#$%&*+-/=@"[..])), 0..8),
+        0..8,
+    )) {
+        let mut input = String::new();
+        for row in &rows {
+            let row = row.iter().map(|&(n, sym)| format!("{n}{}.", char::from(sym)));
+            let row = row.collect::<String>();
+            input += &format!("{row}\n{}\n", ".".repeat(row.len()));
+        }
+        let expected = rows.iter().flatten().map(|&(n, _)| n).sum::<u32>();
+        proptest::prop_assert_eq!(sum(&parse(input.as_bytes()).unwrap()).unwrap(), expected);
+    }
 }
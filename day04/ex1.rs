@@ -1,82 +1,179 @@
+use adventofcode2023::lines::{self, LineSource, Lines};
+use adventofcode2023::{input, Error, ParseError};
 use std::collections::HashSet;
-use std::fs::File;
 use std::io;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
+use std::process::ExitCode;
 
-const NUM_LEN: usize = 2;
-const MAX_WINNING_NUMS: usize = 10;
+const DAY: u8 = 4;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day04/input")?;
-    let begin = Instant::now();
-    println!("{}", score(f));
-    println!("took: {:?}", Instant::now() - begin);
-    Ok(())
-}
+const MAX_WINNING_NUMS: usize = 10; // capacity hint
 
-fn score<R: io::Read>(r: R) -> u32 {
-    let r = BufReader::new(r);
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
 
-    let data_start = "Card xxx:".len();
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", score(&parse(f)?)?);
+    Ok(())
+}
 
-    let mut cur_num = String::with_capacity(NUM_LEN);
-    let mut winning_nums: HashSet<u32> = HashSet::with_capacity(MAX_WINNING_NUMS);
-    let mut cur_acc = 0;
+/// Count of the numbers we have which are winning numbers, for every card.
+pub fn parse<R: io::Read>(r: R) -> Result<Vec<u32>, Error> {
+    let mut winning_nums = HashSet::with_capacity(MAX_WINNING_NUMS);
+    Lines::new(r).parse_each(|i, l| Ok(parse_card(i, l, &mut winning_nums)?))
+}
 
-    r.lines().fold(0, |acc, l| match l {
-        Ok(l) => {
-            let mut iter = l.chars();
+// Same as `parse`, for an input held in memory.
+pub fn parse_bytes(input: &[u8]) -> Result<Vec<u32>, Error> {
+    let mut winning_nums = HashSet::with_capacity(MAX_WINNING_NUMS);
+    lines::split(input).parse_each(|i, l| Ok(parse_card(i, l, &mut winning_nums)?))
+}
 
-            iter.nth(data_start - 1);
+pub fn score(matches: &[u32]) -> Result<u32, Error> {
+    matches.iter().try_fold(0u32, |acc, &m| add_points(acc, m))
+}
 
-            winning_nums.clear();
-            while iter.next().is_some() {
-                let c = iter.next().unwrap();
-                if c == '|' {
-                    break;
-                }
-                cur_num.clear();
+// Same as `score` straight from the reader, one card at a time.
+pub fn stream<R: io::Read>(r: R) -> Result<u32, Error> {
+    let mut winning_nums = HashSet::with_capacity(MAX_WINNING_NUMS);
+    Lines::new(r).try_fold(0u32, |acc, (i, l)| {
+        add_points(acc, parse_card(i, l, &mut winning_nums)?)
+    })
+}
 
-                if c.is_ascii_digit() {
-                    cur_num.push(c);
-                }
-                cur_num.push(iter.next().unwrap());
+// Adds the points of a card with this many matches to `acc`.
+fn add_points(acc: u32, matches: u32) -> Result<u32, Error> {
+    // 1 point for the first match, doubled by each of the others
+    let points = match matches {
+        0 => Some(0),
+        m => 1u32.checked_shl(m - 1),
+    };
+    points
+        .and_then(|p| acc.checked_add(p))
+        .ok_or(Error::NoAnswer("the score overflows u32"))
+}
 
-                winning_nums.insert(cur_num.parse().unwrap());
-            }
+/// Count of the matches of card `l`, using `winning_nums` as a buffer.
+pub fn parse_card(i: usize, l: &[u8], winning_nums: &mut HashSet<u32>) -> Result<u32, ParseError> {
+    // "Card <id>: <winning numbers> | <numbers we have>"
+    if !l.starts_with(b"Card ") {
+        let found = l.split(|&b| b == b' ').next().unwrap_or_default();
+        let found = String::from_utf8_lossy(found);
+        return Err(ParseError::new(DAY, i, 0, found, "\"Card <id>: \""));
+    }
+    let colon =
+        l.iter()
+            .position(|&b| b == b':')
+            .ok_or(ParseError::new(DAY, i, l.len(), "", "':'"))?;
+    let id = &l["Card ".len()..colon];
+    let id = &id[id.iter().take_while(|&&b| b == b' ').count()..];
+    if lines::number::<u32>(id).is_none() {
+        let found = String::from_utf8_lossy(id);
+        return Err(ParseError::new(DAY, i, colon - id.len(), found, "card id"));
+    }
+    let bar = l[colon..]
+        .iter()
+        .position(|&b| b == b'|')
+        .map(|j| colon + j)
+        .ok_or(ParseError::new(DAY, i, l.len(), "", "'|'"))?;
 
-            cur_acc = 0;
-            while iter.next().is_some() {
-                cur_num.clear();
+    winning_nums.clear();
+    for_each_number(i, l, colon + 1, bar, |n| {
+        winning_nums.insert(n);
+    })?;
 
-                let c = iter.next().unwrap();
-                if c.is_ascii_digit() {
-                    cur_num.push(c);
-                }
-                cur_num.push(iter.next().unwrap());
+    let mut matches = 0;
+    for_each_number(i, l, bar + 1, l.len(), |n| {
+        if winning_nums.contains(&n) {
+            matches += 1;
+        }
+    })?;
+    Ok(matches)
+}
 
-                if winning_nums.contains(&cur_num.parse().unwrap()) {
-                    cur_acc = if cur_acc == 0 { 1 } else { cur_acc * 2 }
-                }
+// Calls `f` with each of the space-separated numbers of line `l` between bytes `start` and `end`.
+fn for_each_number(
+    i: usize,
+    l: &[u8],
+    start: usize,
+    end: usize,
+    mut f: impl FnMut(u32),
+) -> Result<(), ParseError> {
+    let mut j = start;
+    while j < end {
+        if l[j] == b' ' {
+            j += 1;
+            continue;
+        }
+        let token = j;
+        while j < end && l[j] != b' ' {
+            if !l[j].is_ascii_digit() {
+                return Err(ParseError::at(DAY, i, l, j, "digit"));
             }
-
-            acc + cur_acc
+            j += 1;
         }
-        Err(e) => panic!("{e}"),
-    })
+        match lines::number(&l[token..j]) {
+            Some(n) => f(n),
+            None => return Err(ParseError::token(DAY, i, l, j, &l[token..j], "number")),
+        }
+    }
+    Ok(())
 }
 
 #[test]
-fn sample_input() {
+fn malformed_number() {
     let input = "\
         Card   1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n\
-        Card   2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n\
-        Card   3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\n\
-        Card   4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\n\
-        Card   5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\n\
-        Card   6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11\n\
+        Card   2: 13 32 20 16 61 | 61 30 6x\n\
         "
     .as_bytes();
-    assert_eq!(score(input), 13)
+    match parse(input) {
+        Err(Error::Parse(e)) => assert_eq!(e, ParseError::new(DAY, 1, 34, "x", "digit")),
+        r => panic!("unexpected result {r:?}"),
+    }
+    let error = |l: &str| parse_card(0, l.as_bytes(), &mut HashSet::new()).unwrap_err();
+    assert_eq!(
+        error("Crd 1: 41 | 83"),
+        ParseError::new(DAY, 0, 0, "Crd", "\"Card <id>: \"")
+    );
+    assert_eq!(
+        error("Card  x1: 41 | 83"),
+        ParseError::new(DAY, 0, 6, "x1", "card id")
+    );
+}
+
+#[test]
+fn larger_than_puzzle_input() {
+    // Every card wins a copy of the next one.
+    let input = (1..=1200)
+        .map(|id| {
+            format!(
+                "Card {id}: 100 200 | 300 {} 1000\n",
+                if id % 2 == 0 { 100 } else { 200 }
+            )
+        })
+        .collect::<String>();
+    assert_eq!(stream(input.as_bytes()).unwrap(), 1200);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn round_trip(numbers in proptest::collection::vec(0..100u32, 0..20)) {
+        let line = format!(
+            "Card 1: {} | 1",
+            numbers.iter().map(|n| format!("{n:>2}")).collect::<Vec<_>>().join(" ")
+        );
+        let mut parsed = Vec::new();
+        for_each_number(0, line.as_bytes(), "Card 1:".len(), line.len() - "| 1".len(), |n| parsed.push(n))
+            .unwrap();
+        proptest::prop_assert_eq!(parsed, numbers);
+    }
 }
@@ -1,91 +1,86 @@
+use adventofcode2023::day04::ex1;
+use adventofcode2023::lines::Lines;
+use adventofcode2023::{input, Error};
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
 use std::io;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
+use std::process::ExitCode;
 
-const NUM_LEN: usize = 2;
-const MAX_WINNING_NUMS: usize = 10;
+const DAY: u8 = 4;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day04/input")?;
-    let begin = Instant::now();
-    println!("{}", cards(f));
-    println!("took: {:?}", Instant::now() - begin);
-    Ok(())
-}
-
-fn cards<R: io::Read>(r: R) -> u64 {
-    let r = BufReader::new(r);
-
-    let data_start = "Card xxx:".len();
-
-    let mut cur_num = String::with_capacity(NUM_LEN);
-    let mut winning_nums: HashSet<u32> = HashSet::with_capacity(MAX_WINNING_NUMS);
-    let mut cur_winning = 0;
-
-    let mut cur_copies = 0;
-    let mut next_copies = HashMap::with_capacity(MAX_WINNING_NUMS);
+const MAX_WINNING_NUMS: usize = 10; // capacity hint
 
-    r.lines().enumerate().fold(0, |acc, (i, l)| match l {
-        Ok(l) => {
-            let mut iter = l.chars();
-
-            iter.nth(data_start - 1);
-
-            winning_nums.clear();
-            while iter.next().is_some() {
-                let c = iter.next().unwrap();
-                if c == '|' {
-                    break;
-                }
-                cur_num.clear();
-
-                if c.is_ascii_digit() {
-                    cur_num.push(c);
-                }
-                cur_num.push(iter.next().unwrap());
-
-                winning_nums.insert(cur_num.parse().unwrap());
-            }
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
 
-            cur_copies = next_copies.remove(&i).unwrap_or(0) + 1;
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", cards(&ex1::parse(f)?)?);
+    Ok(())
+}
 
-            cur_winning = 0;
-            while iter.next().is_some() {
-                cur_num.clear();
+pub fn cards(matches: &[u32]) -> Result<u64, Error> {
+    let mut cards = Cards::new();
+    (0..)
+        .zip(matches)
+        .try_fold(0u64, |acc, (i, &m)| cards.add(acc, i, m))
+}
 
-                let c = iter.next().unwrap();
-                if c.is_ascii_digit() {
-                    cur_num.push(c);
-                }
-                cur_num.push(iter.next().unwrap());
+// Same as `cards` straight from the reader, one card at a time.
+pub fn stream<R: io::Read>(r: R) -> Result<u64, Error> {
+    let mut winning_nums = HashSet::with_capacity(MAX_WINNING_NUMS);
+    let mut cards = Cards::new();
+    Lines::new(r).try_fold(0u64, |acc, (i, l)| {
+        cards.add(acc, i, ex1::parse_card(i, l, &mut winning_nums)?)
+    })
+}
 
-                if winning_nums.contains(&cur_num.parse().unwrap()) {
-                    cur_winning += 1;
-                    next_copies
-                        .entry(i + cur_winning)
-                        .and_modify(|n| *n += cur_copies)
-                        .or_insert(cur_copies);
-                }
-            }
+struct Cards {
+    next_copies: HashMap<usize, u64>, // copies won by the next cards, by line
+}
 
-            acc + cur_copies
+impl Cards {
+    fn new() -> Self {
+        Cards {
+            next_copies: HashMap::with_capacity(MAX_WINNING_NUMS),
         }
-        Err(e) => panic!("{e}"),
-    })
+    }
+
+    // Adds the count of copies of card `i`, which has this many matches, to `acc`.
+    fn add(&mut self, acc: u64, i: usize, matches: u32) -> Result<u64, Error> {
+        let overflow = || Error::NoAnswer("the count of cards overflows u64");
+        let cur_copies = self
+            .next_copies
+            .remove(&i)
+            .unwrap_or(0u64)
+            .checked_add(1)
+            .ok_or_else(overflow)?;
+
+        for won in i + 1..=i + matches as usize {
+            let copies = self.next_copies.entry(won).or_insert(0);
+            *copies = copies.checked_add(cur_copies).ok_or_else(overflow)?;
+        }
+
+        acc.checked_add(cur_copies).ok_or_else(overflow)
+    }
 }
 
 #[test]
-fn sample_input() {
-    let input = "\
-        Card   1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n\
-        Card   2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n\
-        Card   3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\n\
-        Card   4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\n\
-        Card   5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\n\
-        Card   6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11\n\
-        "
-    .as_bytes();
-    assert_eq!(cards(input), 30)
+fn larger_than_puzzle_input() {
+    // Every card wins a copy of the next one.
+    let input = (1..=1200)
+        .map(|id| {
+            format!(
+                "Card {id}: 100 200 | 300 {} 1000\n",
+                if id % 2 == 0 { 100 } else { 200 }
+            )
+        })
+        .collect::<String>();
+    assert_eq!(stream(input.as_bytes()).unwrap(), 720600);
 }
@@ -0,0 +1,42 @@
+use crate::{Error, Solution};
+use std::io;
+
+// Both parts are also standalone binaries, whose main() is unused here.
+#[allow(dead_code)]
+pub mod ex1;
+#[allow(dead_code)]
+pub mod ex2;
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<u32>;
+    type Answer1 = u32;
+    type Answer2 = u64;
+
+    fn parse<R: io::Read>(r: R) -> Result<Self::Input, Error> {
+        ex1::parse(r)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, Error> {
+        ex1::score(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, Error> {
+        ex2::cards(input)
+    }
+
+    fn parse_bytes(input: &[u8]) -> Result<Self::Input, Error> {
+        ex1::parse_bytes(input)
+    }
+
+    fn stream1<R: io::Read>(r: R) -> Result<Self::Answer1, Error> {
+        ex1::stream(r)
+    }
+
+    fn stream2<R: io::Read>(r: R) -> Result<Self::Answer2, Error> {
+        ex2::stream(r)
+    }
+}
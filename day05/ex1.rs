@@ -1,43 +1,53 @@
-use std::fs::File;
+use adventofcode2023::lines::{self, LineSource, Lines};
+use adventofcode2023::{input, Error, ParseError};
 use std::io;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
+use std::process::ExitCode;
 
+const DAY: u8 = 5;
+
+// Capacity hints, from the shape of the puzzle input.
 const MAX_SEEDS: usize = 20;
-const MAX_N_DIGITS: usize = 10;
+const MAX_MAP_SIZE: usize = 50;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
 
-fn main() -> io::Result<()> {
-    let f = File::open("day05/input")?;
-    let begin = Instant::now();
-    println!("{}", lowest(f));
-    println!("took: {:?}", Instant::now() - begin);
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", lowest(&parse(f)?)?);
     Ok(())
 }
 
 #[derive(Ord, PartialOrd, Eq, PartialEq)]
-struct MapRange(u64, u64, usize); // src_start, dst_start, range_len
+pub struct MapRange(pub u64, pub u64, pub usize); // src_start, dst_start, range_len
+
+/// Seeds and the seven maps which lead them to locations, one after the other.
+pub struct Almanac {
+    pub seeds: Vec<(u64, usize /*end idx*/)>,
+    pub seeds_line: (usize, Vec<u8>), // index and text, for errors about the seeds
+    pub maps: [Vec<MapRange>; 7],     // ranges sorted by source
+}
 
 // Required to avoid error:
 //    cannot define inherent `impl` for a type outside of the crate where the type is defined
 trait Mapper {
-    fn new() -> Self;
     fn map(&self, src: u64) -> u64;
 }
 
-type MapRanges = Vec<MapRange>;
-
-impl Mapper for MapRanges {
-    fn new() -> Self {
-        const MAX_MAP_SIZE: usize = 50;
-        Vec::with_capacity(MAX_MAP_SIZE)
-    }
-
+impl Mapper for [MapRange] {
     fn map(&self, src: u64) -> u64 {
         for mr in self {
             if src < mr.0 {
                 // src is between two ranges. Ranges are sorted and do not overlap.
                 return src;
-            } else if src <= mr.0 + (mr.2 - 1) as u64 {
+            } else if src - mr.0 < mr.2 as u64 {
                 return mr.1 + (src - mr.0);
             }
         }
@@ -45,171 +55,191 @@ impl Mapper for MapRanges {
     }
 }
 
-fn lowest<R: io::Read>(r: R) -> u64 {
-    let mut lines = BufReader::new(r).lines();
+pub fn parse<R: io::Read>(r: R) -> Result<Almanac, Error> {
+    parse_lines(Lines::new(r))
+}
 
-    let mut seeds = Vec::with_capacity(MAX_SEEDS);
+// Same as `parse`, for an input held in memory.
+pub fn parse_bytes(input: &[u8]) -> Result<Almanac, Error> {
+    parse_lines(lines::split(input))
+}
 
-    let mut seed_str: String = String::with_capacity(MAX_N_DIGITS);
-    if let Some(Ok(l_seeds)) = lines.next() {
-        let mut seed_chars = l_seeds.chars();
-        seed_chars.nth("seeds:".len());
-        for c in seed_chars {
-            if c == ' ' {
-                match seed_str.parse::<u64>() {
-                    Ok(v) => seeds.push(v),
-                    Err(e) => panic!("{e}"),
-                }
-                seed_str.clear();
-                continue;
-            }
-            seed_str.push(c);
+pub fn lowest(almanac: &Almanac) -> Result<u64, Error> {
+    almanac
+        .seeds
+        .iter()
+        .map(|&(s, _)| almanac.maps.iter().fold(s, |acc, e| e.map(acc)))
+        .min()
+        .ok_or(Error::NoAnswer("no seeds"))
+}
+
+fn parse_lines(mut lines: impl LineSource) -> Result<Almanac, Error> {
+    let mut seeds = Vec::with_capacity(MAX_SEEDS);
+    let mut seeds_line = (0, Vec::new());
+
+    if let Some(l) = lines.next_line() {
+        let (i, l_seeds) = l?;
+        let Some(seeds_str) = l_seeds.strip_prefix(b"seeds: ") else {
+            let found = l_seeds.split(|&b| b == b' ').next().unwrap_or_default();
+            let found = String::from_utf8_lossy(found);
+            return Err(ParseError::new(DAY, i, 0, found, "\"seeds: \"").into());
+        };
+        for seed in seeds_str.split(|&b| b == b' ') {
+            let end = end_of(l_seeds, seed);
+            let Some(v) = lines::number(seed) else {
+                return Err(ParseError::token(DAY, i, l_seeds, end, seed, "seed").into());
+            };
+            seeds.push((v, end));
         }
-        match seed_str.parse::<u64>() {
-            Ok(v) => seeds.push(v),
-            Err(e) => panic!("{e}"),
+        seeds_line = (i, l_seeds.to_vec());
+    }
+    if let Some(l) = lines.next_line() {
+        let (i, l) = l?;
+        if !l.is_empty() {
+            return Err(ParseError::at(DAY, i, l, 0, "empty line").into());
         }
     }
-    lines.nth(1); // step over the first "SRC-to-DST map:" line
-
-    let maps: [&mut MapRanges; 7] = [
-        &mut Mapper::new(), // seed to soil
-        &mut Mapper::new(), // soil to fertilizer
-        &mut Mapper::new(), // fertilizer to water
-        &mut Mapper::new(), // water to light
-        &mut Mapper::new(), // light to temperature
-        &mut Mapper::new(), // temperature to humidity
-        &mut Mapper::new(), // humidity to location
-    ];
+
+    // seed to soil, soil to fertilizer, ..., humidity to location
+    let mut maps: [Vec<MapRange>; 7] = std::array::from_fn(|_| Vec::with_capacity(MAX_MAP_SIZE));
 
     let mut cur_map = 0;
-    let mut n_str = String::with_capacity(MAX_N_DIGITS);
-    while let Some(Ok(l)) = lines.next() {
-        if l.is_empty() {
-            maps[cur_map].sort();
-            cur_map += 1;
-            lines.next(); // causes the next iteration to step over the "SRC-to-DST map:" line
+    let mut in_map = false; // past the "SRC-to-DST map:" line of the current map
+    while let Some(l) = lines.next_line() {
+        let (i, l) = l?;
+        if !in_map {
+            let names = l.strip_suffix(b" map:");
+            let names = names.and_then(|h| lines::split_once(h, b"-to-"));
+            if !names.is_some_and(|(src, dst)| is_name(src) && is_name(dst)) {
+                let found = String::from_utf8_lossy(l);
+                return Err(ParseError::new(DAY, i, 0, found, "\"<a>-to-<b> map:\"").into());
+            }
+            in_map = true;
             continue;
         }
-        n_str.clear();
-
-        let mut src_start: u64 = 0;
-        let mut dst_start: u64 = 0;
-
-        let mut parsed_first = false;
-        for c in l.chars() {
-            if c == ' ' {
-                let v: u64 = match n_str.parse() {
-                    Ok(v) => v,
-                    Err(e) => panic!("{e}"),
-                };
-                if parsed_first {
-                    src_start = v;
-                } else {
-                    parsed_first = true;
-                    dst_start = v;
-                };
-                n_str.clear();
-            } else {
-                n_str.push(c);
+        if l.is_empty() {
+            cur_map += 1;
+            if cur_map == maps.len() {
+                break;
             }
+            in_map = false;
+            continue;
         }
+        maps[cur_map].push(parse_range(i, l)?);
+    }
+    if let Some(l) = lines.next_line() {
+        let (i, found) = l?;
+        let found = String::from_utf8_lossy(found);
+        return Err(ParseError::new(DAY, i, 0, found, "end of input").into());
+    }
+    for map in &mut maps {
+        map.sort();
+    }
 
-        let range_len: usize = match n_str.parse() {
-            Ok(v) => v,
-            Err(e) => panic!("{e}"),
-        };
+    Ok(Almanac {
+        seeds,
+        seeds_line,
+        maps,
+    })
+}
 
-        maps[cur_map].push(MapRange(src_start, dst_start, range_len));
+// "<dst_start> <src_start> <range_len>" line of a map.
+fn parse_range(i: usize, l: &[u8]) -> Result<MapRange, ParseError> {
+    let mut nums = l.split(|&b| b == b' ');
+    let mut next = || nums.next().unwrap_or(&l[l.len()..]);
+
+    let mut starts = [0u64; 2];
+    for start in &mut starts {
+        let n_str = next();
+        let Some(v) = lines::number(n_str) else {
+            let end = end_of(l, n_str);
+            return Err(ParseError::token(DAY, i, l, end, n_str, "number"));
+        };
+        *start = v;
+    }
+    let [dst_start, src_start] = starts;
+
+    // Ranges must not extend past u64::MAX.
+    let fits = |len: usize| {
+        [src_start, dst_start]
+            .iter()
+            .all(|s| s.checked_add(len as u64).is_some())
+    };
+    let len_str = next();
+    let end = end_of(l, len_str);
+    let Some(range_len) = lines::number(len_str).filter(|&len| fits(len)) else {
+        return Err(ParseError::token(DAY, i, l, end, len_str, "range length"));
+    };
+    if end < l.len() {
+        return Err(ParseError::at(DAY, i, l, end, "end of line"));
     }
-    maps[cur_map].sort();
 
-    seeds
-        .iter()
-        .map(|s| maps.iter().fold(*s, |acc, e| e.map(acc)))
-        .min()
-        .unwrap_or_default()
+    Ok(MapRange(src_start, dst_start, range_len))
+}
+
+// Name of a category in a "<a>-to-<b> map:" line.
+fn is_name(name: &[u8]) -> bool {
+    !name.is_empty() && name.iter().all(|b| b.is_ascii_lowercase())
+}
+
+// Byte offset of the end of `token`, a slice of `line`.
+fn end_of(line: &[u8], token: &[u8]) -> usize {
+    token.as_ptr() as usize - line.as_ptr() as usize + token.len()
 }
 
 #[test]
-fn sample_input_no_gaps() {
-    let input = "\
-        seeds: 79 14 55 13\n\
-        \n\
-        seed-to-soil map:\n\
-        50 98 2\n\
-        52 50 48\n\
-        \n\
-        soil-to-fertilizer map:\n\
-        0 15 37\n\
-        37 52 2\n\
-        39 0 15\n\
-        \n\
-        fertilizer-to-water map:\n\
-        49 53 8\n\
-        0 11 42\n\
-        42 0 7\n\
-        57 7 4\n\
-        \n\
-        water-to-light map:\n\
-        88 18 7\n\
-        18 25 70\n\
-        \n\
-        light-to-temperature map:\n\
-        45 77 23\n\
-        81 45 19\n\
-        68 64 13\n\
-        \n\
-        temperature-to-humidity map:\n\
-        0 69 1\n\
-        1 0 69\n\
-        \n\
-        humidity-to-location map:\n\
-        56 93 4\n\
-        60 56 37\n\
-        "
-    .as_bytes();
-    assert_eq!(lowest(input), 35)
+fn larger_than_puzzle_input() {
+    let seeds = (100..130)
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let input = format!("seeds: {seeds} 1000\n\nseed-to-soil map:\n0 1000 1\n");
+    assert_eq!(lowest(&parse(input.as_bytes()).unwrap()).unwrap(), 0);
 }
 
 #[test]
-// 13: -> 13 -> 13 -> 13 -> 13 -> 14 -> 14
-fn ranges_with_gaps() {
-    let input = "\
-        seeds: 13\n\
-        \n\
-        seed-to-soil map:\n\
-        50 98 2\n\
-        52 50 48\n\
-        \n\
-        soil-to-fertilizer map:\n\
-        0 15 1\n\
-        37 52 2\n\
-        39 10 1\n\
-        \n\
-        fertilizer-to-water map:\n\
-        49 53 8\n\
-        0 14 42\n\
-        42 0 7\n\
-        57 7 4\n\
-        \n\
-        water-to-light map:\n\
-        88 18 7\n\
-        18 25 70\n\
-        \n\
-        light-to-temperature map:\n\
-        45 77 23\n\
-        81 45 19\n\
-        68 64 13\n\
-        \n\
-        temperature-to-humidity map:\n\
-        0 69 1\n\
-        1 0 69\n\
-        \n\
-        humidity-to-location map:\n\
-        56 93 4\n\
-        60 56 37\n\
-        "
-    .as_bytes();
-    assert_eq!(lowest(input), 14)
+fn malformed_maps() {
+    let error = |input: &str| match parse(input.as_bytes()) {
+        Err(Error::Parse(e)) => e,
+        Err(e) => panic!("unexpected error {e:?}"),
+        Ok(_) => panic!("{input:?} is parsed"),
+    };
+    let seeds = "seeds: 79 14\n\nseed-to-soil map:\n";
+    assert_eq!(
+        error(&format!("{seeds}50 98\n")),
+        ParseError::new(DAY, 3, 5, "", "range length")
+    );
+    assert_eq!(
+        error(&format!("{seeds}50 98 2 7 9\n")),
+        ParseError::new(DAY, 3, 7, " ", "end of line")
+    );
+    // Without the empty line or the header of a map, lines are not taken for one another.
+    assert_eq!(
+        error("seeds: 79 14\nseed-to-soil map:\n50 98 2\n"),
+        ParseError::new(DAY, 1, 0, "s", "empty line")
+    );
+    assert_eq!(
+        error(&format!("{seeds}50 98 2\n\n52 50 48\n")),
+        ParseError::new(DAY, 5, 0, "52 50 48", "\"<a>-to-<b> map:\"")
+    );
+    assert_eq!(
+        error("seed: 79 14\n"),
+        ParseError::new(DAY, 0, 0, "seed:", "\"seeds: \"")
+    );
+    assert!(matches!(
+        parse("".as_bytes()).and_then(|a| lowest(&a)),
+        Err(Error::NoAnswer(_))
+    ));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    // Seeds which the only map does not move, so that the lowest location is the lowest seed.
+    #[test]
+    fn round_trip(seeds in proptest::collection::vec(1..u64::MAX, 1..30)) {
+        let seeds_str = seeds.iter().map(u64::to_string).collect::<Vec<_>>().join(" ");
+        let input = format!("seeds: {seeds_str}\n\nseed-to-soil map:\n5 0 1\n");
+        let expected = *seeds.iter().min().unwrap();
+        proptest::prop_assert_eq!(lowest(&parse(input.as_bytes()).unwrap()).unwrap(), expected);
+    }
 }
@@ -1,41 +1,39 @@
-use std::fs::File;
-use std::io;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
+use adventofcode2023::day05::ex1::{self, Almanac, MapRange};
+use adventofcode2023::{input, Error, ParseError};
+use std::process::ExitCode;
 
+const DAY: u8 = 5;
+
+// Capacity hints, from the shape of the puzzle input.
 const MAX_SEEDS: usize = 20;
 const MAX_SEED_PAIRS: usize = MAX_SEEDS / 2;
-const MAX_N_DIGITS: usize = 10;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day05/input")?;
-    let begin = Instant::now();
-    println!("{}", lowest(f));
-    println!("took: {:?}", Instant::now() - begin);
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", lowest(&ex1::parse(f)?)?);
     Ok(())
 }
 
 #[derive(Ord, PartialOrd, Eq, PartialEq, Clone)]
 struct Range(u64, u64); // [rng_start, rng_end)
 
-#[derive(Ord, PartialOrd, Eq, PartialEq)]
-struct MapRange(u64, u64, usize); // src_start, dst_start, range_len
-
 // Required to avoid error:
 //    cannot define inherent `impl` for a type outside of the crate where the type is defined
 trait Mapper {
-    fn new() -> Self;
     fn map(&self, input: Vec<Range>) -> Vec<Range>;
 }
 
-type MapRanges = Vec<MapRange>;
-
-impl Mapper for MapRanges {
-    fn new() -> Self {
-        const MAX_MAP_SIZE: usize = 50;
-        Vec::with_capacity(MAX_MAP_SIZE)
-    }
-
+impl Mapper for [MapRange] {
     fn map(&self, mut input: Vec<Range>) -> Vec<Range> {
         let mut transformed_rngs = Vec::with_capacity(input.len() * 2); // empirical cap value
         for mr in self {
@@ -100,146 +98,69 @@ impl Mapper for MapRanges {
     }
 }
 
-fn lowest<R: io::Read>(r: R) -> u64 {
-    let mut lines = BufReader::new(r).lines();
-
+pub fn lowest(almanac: &Almanac) -> Result<u64, Error> {
+    let (i, l_seeds) = &almanac.seeds_line;
     let mut seed_rngs = Vec::with_capacity(MAX_SEED_PAIRS);
-
-    let mut seed_str: String = String::with_capacity(MAX_N_DIGITS);
-    if let Some(Ok(l_seeds)) = lines.next() {
-        let mut seed_chars = l_seeds.chars();
-        seed_chars.nth("seeds:".len());
-
-        let mut seed: u64 = u64::MAX;
-        for c in seed_chars {
-            if c == ' ' {
-                if seed == u64::MAX {
-                    seed = match seed_str.parse() {
-                        Ok(v) => v,
-                        Err(e) => panic!("{e}"),
-                    }
-                } else {
-                    match seed_str.parse::<usize>() {
-                        Ok(v) => seed_rngs.push(Range(seed, seed + v as u64)),
-                        Err(e) => panic!("{e}"),
-                    };
-                    seed = u64::MAX;
-                }
-                seed_str.clear();
-                continue;
-            }
-            seed_str.push(c);
-        }
-        match seed_str.parse::<usize>() {
-            Ok(v) => seed_rngs.push(Range(seed, seed + v as u64)),
-            Err(e) => panic!("{e}"),
+    for pair in almanac.seeds.chunks(2) {
+        let [(start, _), (len, end)] = *pair else {
+            let (end, expected) = (l_seeds.len(), "seed range length");
+            return Err(ParseError::new(DAY, *i, end, "", expected).into());
         };
+        seed_rngs.push(parse_range(*i, l_seeds, end, len, start)?);
     }
-    lines.nth(1); // step over the first "SRC-to-DST map:" line
-
-    let maps: [&mut MapRanges; 7] = [
-        &mut Mapper::new(), // seed to soil
-        &mut Mapper::new(), // soil to fertilizer
-        &mut Mapper::new(), // fertilizer to water
-        &mut Mapper::new(), // water to light
-        &mut Mapper::new(), // light to temperature
-        &mut Mapper::new(), // temperature to humidity
-        &mut Mapper::new(), // humidity to location
-    ];
-
-    let mut cur_map = 0;
-    let mut n_str = String::with_capacity(MAX_N_DIGITS);
-    while let Some(Ok(l)) = lines.next() {
-        if l.is_empty() {
-            maps[cur_map].sort();
-            cur_map += 1;
-            lines.next(); // causes the next iteration to step over the "SRC-to-DST map:" line
-            continue;
-        }
-        n_str.clear();
-
-        let mut src_start: u64 = 0;
-        let mut dst_start: u64 = 0;
-
-        let mut parsed_first = false;
-        for c in l.chars() {
-            if c == ' ' {
-                let v: u64 = match n_str.parse() {
-                    Ok(v) => v,
-                    Err(e) => panic!("{e}"),
-                };
-                if parsed_first {
-                    src_start = v;
-                } else {
-                    parsed_first = true;
-                    dst_start = v;
-                };
-                n_str.clear();
-            } else {
-                n_str.push(c);
-            }
-        }
-
-        let range_len: usize = match n_str.parse() {
-            Ok(v) => v,
-            Err(e) => panic!("{e}"),
-        };
-
-        maps[cur_map].push(MapRange(src_start, dst_start, range_len));
-    }
-    maps[cur_map].sort();
 
     seed_rngs
         .iter()
-        .map(|r| {
-            maps.iter()
+        .filter_map(|r| {
+            // empty seed ranges map to no location
+            almanac
+                .maps
+                .iter()
                 .fold(vec![r.clone()], |acc, e| e.map(acc))
                 .iter()
                 .min()
-                .unwrap()
-                .0
+                .map(|r| r.0)
         })
         .min()
-        .unwrap_or_default()
+        .ok_or(Error::NoAnswer("no seeds"))
+}
+
+// Range of seeds starting at `start`, whose length `len` ends at byte `end` of the seeds line.
+fn parse_range(
+    i: usize,
+    line: &[u8],
+    end: usize,
+    len: u64,
+    start: u64,
+) -> Result<Range, ParseError> {
+    match start.checked_add(len) {
+        Some(range_end) => Ok(Range(start, range_end)),
+        None => {
+            let len_str = line[..end]
+                .rsplit(|&b| b == b' ')
+                .next()
+                .unwrap_or_default();
+            let expected = "seed range length";
+            Err(ParseError::token(DAY, i, line, end, len_str, expected))
+        }
+    }
 }
 
 #[test]
-fn sample_input_no_gaps() {
-    let input = "\
-        seeds: 79 14 55 13\n\
-        \n\
-        seed-to-soil map:\n\
-        50 98 2\n\
-        52 50 48\n\
-        \n\
-        soil-to-fertilizer map:\n\
-        0 15 37\n\
-        37 52 2\n\
-        39 0 15\n\
-        \n\
-        fertilizer-to-water map:\n\
-        49 53 8\n\
-        0 11 42\n\
-        42 0 7\n\
-        57 7 4\n\
-        \n\
-        water-to-light map:\n\
-        88 18 7\n\
-        18 25 70\n\
-        \n\
-        light-to-temperature map:\n\
-        45 77 23\n\
-        81 45 19\n\
-        68 64 13\n\
-        \n\
-        temperature-to-humidity map:\n\
-        0 69 1\n\
-        1 0 69\n\
-        \n\
-        humidity-to-location map:\n\
-        60 56 37\n\
-        56 93 4\n\
-        "
-    .as_bytes();
-    assert_eq!(lowest(input), 46)
+fn larger_than_puzzle_input() {
+    let seeds = (0..15)
+        .map(|k| format!("{} 5", 100 + 10 * k))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let input = format!("seeds: {seeds} 1000 1\n\nseed-to-soil map:\n0 1000 1\n");
+    assert_eq!(lowest(&ex1::parse(input.as_bytes()).unwrap()).unwrap(), 0);
+}
+
+#[test]
+fn odd_seed_count() {
+    let input = "seeds: 79 14 55\n\nseed-to-soil map:\n50 98 2\n".as_bytes();
+    match lowest(&ex1::parse(input).unwrap()) {
+        Err(Error::Parse(e)) => assert_eq!(e, ParseError::new(DAY, 0, 15, "", "seed range length")),
+        r => panic!("unexpected result {r:?}"),
+    }
 }
@@ -0,0 +1,34 @@
+use crate::{Error, Solution};
+use std::io;
+
+// Both parts are also standalone binaries, whose main() is unused here.
+#[allow(dead_code)]
+pub mod ex1;
+#[allow(dead_code)]
+pub mod ex2;
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = ex1::Almanac;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse<R: io::Read>(r: R) -> Result<Self::Input, Error> {
+        ex1::parse(r)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, Error> {
+        ex1::lowest(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, Error> {
+        ex2::lowest(input)
+    }
+
+    fn parse_bytes(input: &[u8]) -> Result<Self::Input, Error> {
+        ex1::parse_bytes(input)
+    }
+}
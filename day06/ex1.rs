@@ -1,71 +1,170 @@
-use std::fs::File;
+use adventofcode2023::lines::{self, LineSource, Lines};
+use adventofcode2023::{input, Error, ParseError};
+use std::cmp::Ordering;
 use std::io;
-use std::io::{BufRead, BufReader};
-use std::ops::ControlFlow;
-use std::time::Instant;
-
-fn main() -> io::Result<()> {
-    let f = File::open("day06/input")?;
-    let begin = Instant::now();
-    println!("{}", product(f));
-    println!("took: {:?}", Instant::now() - begin);
+use std::process::ExitCode;
+
+const DAY: u8 = 6;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", product(&parse(f)?)?);
     Ok(())
 }
 
-fn product<R: io::Read>(r: R) -> u32 {
-    let mut lines = BufReader::new(r).lines();
+/// Lines of the race times and record distances, which each part reads its own way.
+pub struct Sheet {
+    pub times: Values,
+    pub distances: Values,
+}
+
+/// Values of a line after its header, made of digits and spaces only.
+pub struct Values {
+    pub line: usize,   // index
+    pub offset: usize, // of the values in the line, after the header
+    pub text: Vec<u8>,
+}
+
+pub fn parse<R: io::Read>(r: R) -> Result<Sheet, Error> {
+    parse_lines(Lines::new(r))
+}
+
+// Same as `parse`, for an input held in memory.
+pub fn parse_bytes(input: &[u8]) -> Result<Sheet, Error> {
+    parse_lines(lines::split(input))
+}
+
+fn parse_lines(mut lines: impl LineSource) -> Result<Sheet, Error> {
+    let times = parse_values(0, lines.next_line(), "Time:")?;
+    let distances = parse_values(1, lines.next_line(), "Distance:")?;
+    // Each race has a time and a distance.
+    let count = |v: &Values| {
+        v.text
+            .split(|&b| b == b' ')
+            .filter(|s| !s.is_empty())
+            .count()
+    };
+    let shorter = match count(&times).cmp(&count(&distances)) {
+        Ordering::Less => &times,
+        Ordering::Greater => &distances,
+        Ordering::Equal => return Ok(Sheet { times, distances }),
+    };
+    let end = shorter.offset + shorter.text.len();
+    Err(ParseError::new(DAY, shorter.line, end, "", "value").into())
+}
 
-    let hdr_col_len = "Distance:".len();
-    let times = lines.next().unwrap().unwrap()[hdr_col_len..]
-        .split(' ')
-        .filter_map(|s| s.parse().ok())
-        .collect::<Vec<_>>();
-    let dists = lines.next().unwrap().unwrap()[hdr_col_len..]
-        .split(' ')
-        .filter_map(|s| s.parse().ok())
-        .collect::<Vec<_>>();
+pub fn product(sheet: &Sheet) -> Result<u32, Error> {
+    let times = numbers(&sheet.times)?;
+    let dists = numbers(&sheet.distances)?;
 
     times
         .iter()
         .zip(dists.iter())
-        .map(|race| {
-            let race_time = *race.0;
-            let race_dist = *race.1;
-
-            /* Alternative: terse bruteforce using map().sum()
-             *
-             * (1..race_time)
-             *     .map(|press_time| is_win(press_time, race_time, race_dist) as u32)
-             *     .sum()
-             */
-
-            if let ControlFlow::Break(first_win) = (1..race_time).try_for_each(|press_time| {
-                if is_win(press_time, race_time, race_dist) {
-                    ControlFlow::Break(press_time)
-                } else {
-                    ControlFlow::Continue(())
+        .map(|(&race_time, &race_dist)| ways(race_time, race_dist))
+        .try_fold(1u32, |acc, ways| acc.checked_mul(ways))
+        .ok_or(Error::NoAnswer("the product overflows u32"))
+}
+
+// The brute-force alternative is the reference in src/differential.rs.
+fn ways(race_time: u32, race_dist: u32) -> u32 {
+    // The distance increases with the press time up to the middle of the race, so the first win is
+    // looked for in between, where `lo` loses and `hi` wins.
+    let (mut lo, mut hi) = (0, race_time / 2);
+    if !is_win(hi, race_time, race_dist) {
+        return 0;
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if is_win(mid, race_time, race_dist) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    // Wins are symmetric around the middle of the race.
+    race_time - 2 * hi + 1
+}
+
+fn parse_values(
+    i: usize,
+    line: Option<io::Result<(usize, &[u8])>>,
+    header: &'static str,
+) -> Result<Values, Error> {
+    let line = line.transpose()?.map_or(&[][..], |(_, l)| l);
+    let Some(values) = line.strip_prefix(header.as_bytes()) else {
+        let found = String::from_utf8_lossy(&line[..line.len().min(header.len())]);
+        return Err(ParseError::new(DAY, i, 0, found, header).into());
+    };
+    if let Some(j) = values
+        .iter()
+        .position(|&b| !b.is_ascii_digit() && b != b' ')
+    {
+        return Err(ParseError::at(DAY, i, line, header.len() + j, "digit").into());
+    }
+    Ok(Values {
+        line: i,
+        offset: header.len(),
+        text: values.to_vec(),
+    })
+}
+
+// Space-separated numbers of the values.
+fn numbers(values: &Values) -> Result<Vec<u32>, Error> {
+    let mut offset = values.offset;
+    let mut parsed = Vec::new();
+    for s in values.text.split(|&b| b == b' ') {
+        if !s.is_empty() {
+            match lines::number(s) {
+                Some(v) => parsed.push(v),
+                None => {
+                    let found = String::from_utf8_lossy(s);
+                    return Err(ParseError::new(DAY, values.line, offset, found, "number").into());
                 }
-            }) {
-                (race_time + 1) - first_win * 2
-            } else {
-                0
             }
-        })
-        .product()
+        }
+        offset += s.len() + 1;
+    }
+    Ok(parsed)
 }
 
 fn is_win(press_time: u32, race_time: u32, race_dist: u32) -> bool {
-    let speed = press_time;
-    let move_time = race_time - press_time;
-    move_time * speed > race_dist
+    let speed = u64::from(press_time);
+    let move_time = u64::from(race_time - press_time);
+    move_time * speed > u64::from(race_dist)
+}
+
+#[test]
+fn races_without_distance() {
+    match parse("Time: 7 15 30\nDistance: 9 40\n".as_bytes()) {
+        Err(Error::Parse(e)) => assert_eq!(e, ParseError::new(DAY, 1, 14, "", "value")),
+        r => panic!("unexpected result {:?}", r.map(|s| s.times.text)),
+    }
 }
 
 #[test]
-fn sample_input() {
-    let input = "\
-        Time:      7  15   30\n\
-        Distance:  9  40  200\n\
-        "
-    .as_bytes();
-    assert_eq!(product(input), 288)
+fn larger_than_puzzle_input() {
+    // Every race is won by pressing 1 or 2 ms.
+    let input = format!("Time:{}\nDistance:{}\n", " 3".repeat(30), " 1".repeat(30));
+    assert_eq!(product(&parse(input.as_bytes()).unwrap()).unwrap(), 1 << 30);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn round_trip(values in proptest::collection::vec((proptest::num::u32::ANY, 1..5usize), 0..10)) {
+        let line = values.iter().map(|(v, spaces)| format!("{}{v}", " ".repeat(*spaces)));
+        let line = format!("Time:{}", line.collect::<String>());
+        let parsed = numbers(&parse_values(0, Some(Ok((0, line.as_bytes()))), "Time:").unwrap()).unwrap();
+        proptest::prop_assert_eq!(parsed, values.iter().map(|v| v.0).collect::<Vec<_>>());
+    }
 }
@@ -1,81 +1,85 @@
-use core::panic;
-use std::fs::File;
-use std::io;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
+use adventofcode2023::day06::ex1::{self, Sheet, Values};
+use adventofcode2023::{input, lines, Error, ParseError};
+use std::process::ExitCode;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day06/input")?;
-    let begin = Instant::now();
-    println!("{}", ways(f));
-    println!("took: {:?}", Instant::now() - begin);
-    Ok(())
+const DAY: u8 = 6;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn ways<R: io::Read>(r: R) -> u64 {
-    let mut lines = BufReader::new(r).lines();
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", ways(&ex1::parse(f)?)?);
+    Ok(())
+}
 
-    let hdr_col_len = "Distance:".len();
-    let race_time = match lines.next().unwrap().unwrap()[hdr_col_len..]
-        .chars()
-        .filter(|c| c.is_ascii_digit())
-        .collect::<String>()
-        .parse()
-    {
-        Ok(v) => v,
-        Err(e) => panic!("{e}"),
-    };
-    let race_dist = match lines.next().unwrap().unwrap()[hdr_col_len..]
-        .chars()
-        .filter(|c| c.is_ascii_digit())
-        .collect::<String>()
-        .parse()
-    {
-        Ok(v) => v,
-        Err(e) => panic!("{e}"),
-    };
+pub fn ways(sheet: &Sheet) -> Result<u64, Error> {
+    let race_time = number(&sheet.times)?;
+    let race_dist = number(&sheet.distances)?;
 
-    /* Alternative: terse bruteforce using map().sum()
-     *
-     * (1..race_time)
-     *     .map(|press_time| is_win(press_time, race_time, race_dist) as u64)
-     *     .sum()
-     */
+    // The brute-force alternative is the reference in src/differential.rs.
 
-    let mut i = 0;
-    let mut j = race_time / 2;
-    let first_win = loop {
-        if i > j {
-            break 0;
-        }
-        let mid = i + (j - i) / 2;
+    // The distance increases with the press time up to the middle of the race, so the first win is
+    // looked for in between, where `lo` loses and `hi` wins.
+    let (mut lo, mut hi) = (0, race_time / 2);
+    if !is_win(hi, race_time, race_dist) {
+        return Ok(0);
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
         if is_win(mid, race_time, race_dist) {
-            if !is_win(mid - 1, race_time, race_dist) {
-                break mid;
-            }
-            j = mid;
+            hi = mid;
         } else {
-            if is_win(mid + 1, race_time, race_dist) {
-                break mid + 1;
-            }
-            i = mid;
+            lo = mid;
         }
-    };
-    (race_time + 1) - first_win * 2
+    }
+    // Wins are symmetric around the middle of the race.
+    Ok(race_time - 2 * hi + 1)
+}
+
+// Digits of all the values as a single number, ignoring the spaces between them.
+fn number(values: &Values) -> Result<u64, Error> {
+    let digits = values.text.iter().filter(|&&b| b != b' ').copied();
+    match lines::number(&digits.collect::<Vec<_>>()) {
+        Some(v) => Ok(v),
+        None => {
+            let (i, offset) = (values.line, values.offset);
+            let found = String::from_utf8_lossy(&values.text);
+            Err(ParseError::new(DAY, i, offset, found, "number").into())
+        }
+    }
 }
 
 fn is_win(press_time: u64, race_time: u64, race_dist: u64) -> bool {
-    let speed = press_time;
-    let move_time = race_time - press_time;
-    move_time * speed > race_dist
+    let speed = u128::from(press_time);
+    let move_time = u128::from(race_time - press_time);
+    move_time * speed > u128::from(race_dist)
 }
 
 #[test]
-fn sample_input() {
-    let input = "\
-        Time:      7  15   30\n\
-        Distance:  9  40  200\n\
-        "
-    .as_bytes();
-    assert_eq!(ways(input), 71503)
+fn larger_than_puzzle_input() {
+    // More values than races in the puzzle input, read as a single race.
+    let input = "Time: 7 1 5 3 0\nDistance: 9 4 0 2 00\n".as_bytes();
+    assert_eq!(ways(&ex1::parse(input).unwrap()).unwrap(), 71503);
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    // The digits of the values, whatever their spacing, form a single value.
+    #[test]
+    fn round_trip(value in proptest::num::u64::ANY, spaces in proptest::collection::vec(0..4usize, 20)) {
+        let digits = value.to_string();
+        let line = digits.chars().zip(&spaces).map(|(c, &n)| format!("{}{c}", " ".repeat(n)));
+        let line = line.collect::<String>();
+        let input = format!("Time:{line}\nDistance:{line}\n");
+        let parsed = number(&ex1::parse(input.as_bytes()).unwrap().distances).unwrap();
+        proptest::prop_assert_eq!(parsed, value);
+    }
 }
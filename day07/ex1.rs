@@ -1,25 +1,31 @@
-use std::cmp::{Ord, Ordering};
+use adventofcode2023::lines::{self, LineSource, Lines};
+use adventofcode2023::{input, Error, ParseError};
 use std::collections::HashMap;
-use std::convert::Into;
-use std::fs::File;
 use std::io;
-use std::io::{BufRead, BufReader};
-use std::ops::ControlFlow;
-use std::time::Instant;
+use std::process::ExitCode;
+
+const DAY: u8 = 7;
 
 const HAND_SIZE: usize = 5;
-const MAX_HANDS: usize = 1000;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day07/input")?;
-    let begin = Instant::now();
-    println!("{}", total(f));
-    println!("took: {:?}", Instant::now() - begin);
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", total(&parse(f)?)?);
     Ok(())
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
+pub enum HandType {
     HighCard = 0,
     OnePair = 1,
     TwoPair = 2,
@@ -29,126 +35,152 @@ enum HandType {
     FiveOfAKind = 6,
 }
 
-#[derive(PartialEq, Eq)]
-struct Hand([char; HAND_SIZE], u32); // cards, bid
+#[derive(Debug, PartialEq, Eq)]
+pub struct Hand {
+    pub cards: [u8; HAND_SIZE],
+    pub bid: u32,
+}
 
-impl From<&Hand> for HandType {
-    fn from(h: &Hand) -> Self {
-        let mut card_types = HashMap::with_capacity(h.0.len());
-        for c in h.0 {
-            card_types
-                .entry(c)
-                .and_modify(|n| *n += 1)
-                .or_insert(1usize);
-        }
+// Type of the hand, from how many of its cards are alike.
+fn hand_type(cards: &[u8; HAND_SIZE]) -> HandType {
+    let mut card_types = HashMap::with_capacity(cards.len());
+    for &c in cards {
+        card_types
+            .entry(c)
+            .and_modify(|n| *n += 1)
+            .or_insert(1usize);
+    }
 
-        match card_types.len() {
-            1 => HandType::FiveOfAKind,
-            2 => {
-                if *card_types.values().max().unwrap() == 4 {
-                    HandType::FourOfAKind
-                } else {
-                    HandType::FullHouse
-                }
+    match card_types.len() {
+        1 => HandType::FiveOfAKind,
+        2 => {
+            if *card_types.values().max().unwrap() == 4 {
+                HandType::FourOfAKind
+            } else {
+                HandType::FullHouse
             }
-            3 => {
-                if *card_types.values().max().unwrap() == 3 {
-                    HandType::ThreeOfAKind
-                } else {
-                    HandType::TwoPair
-                }
+        }
+        3 => {
+            if *card_types.values().max().unwrap() == 3 {
+                HandType::ThreeOfAKind
+            } else {
+                HandType::TwoPair
             }
-            4 => HandType::OnePair,
-            _ => HandType::HighCard,
         }
+        4 => HandType::OnePair,
+        _ => HandType::HighCard,
     }
 }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+// Strength of the hand: its type, then the values of its cards in order.
+fn strength(hand: &Hand) -> (HandType, [u8; HAND_SIZE]) {
+    (hand_type(&hand.cards), hand.cards.map(card_value))
 }
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let self_type: HandType = self.into();
-        let other_type: HandType = other.into();
-
-        match self_type.cmp(&other_type) {
-            Ordering::Equal => {
-                let cmp = self.0.into_iter().zip(other.0).try_for_each(|cards| {
-                    fn card_value(c: char) -> u8 {
-                        match c {
-                            '2'..='9' => c as u8 - b'2',
-                            'T' => 8,
-                            'J' => 9,
-                            'Q' => 10,
-                            'K' => 11,
-                            'A' => 12,
-                            _ => u8::MAX,
-                        }
-                    }
-                    match card_value(cards.0).cmp(&card_value(cards.1)) {
-                        Ordering::Equal => ControlFlow::Continue(()),
-                        o => ControlFlow::Break(o),
-                    }
-                });
-                if let ControlFlow::Break(cmp) = cmp {
-                    cmp
-                } else {
-                    Ordering::Equal
-                }
-            }
-            o => o,
-        }
+fn card_value(c: u8) -> u8 {
+    match c {
+        b'2'..=b'9' => c - b'2',
+        b'T' => 8,
+        b'J' => 9,
+        b'Q' => 10,
+        b'K' => 11,
+        b'A' => 12,
+        _ => u8::MAX,
     }
 }
 
-fn total<R: io::Read>(r: R) -> u32 {
-    let mut lines = BufReader::new(r).lines();
+pub fn parse<R: io::Read>(r: R) -> Result<Vec<Hand>, Error> {
+    parse_lines(Lines::new(r))
+}
 
-    let mut hands = Vec::with_capacity(MAX_HANDS);
-    while let Some(Ok(l)) = lines.next() {
-        hands.push(parse_draw(l));
-    }
-    hands.sort();
+// Same as `parse`, for an input held in memory.
+pub fn parse_bytes(input: &[u8]) -> Result<Vec<Hand>, Error> {
+    parse_lines(lines::split(input))
+}
 
-    hands
+fn parse_lines(lines: impl LineSource) -> Result<Vec<Hand>, Error> {
+    lines.parse_each(|i, l| Ok(parse_draw(i, l)?))
+}
+
+pub fn total(hands: &[Hand]) -> Result<u32, Error> {
+    winnings(hands, strength)
+}
+
+/// Sum of the bids multiplied by the rank of their hand, from the weakest hand to the strongest
+/// one. Equal hands are ranked in their order of appearance.
+pub fn winnings<S: Ord>(hands: &[Hand], strength: impl Fn(&Hand) -> S) -> Result<u32, Error> {
+    let mut ranked = hands.iter().collect::<Vec<_>>();
+    ranked.sort_by_cached_key(|hand| strength(hand));
+
+    ranked
         .iter()
         .enumerate()
-        .fold(0, |acc, e| acc + (e.0 as u32 + 1) * e.1 .1)
+        .try_fold(0u32, |acc, (rank, hand)| {
+            let rank = u32::try_from(rank + 1).ok()?;
+            acc.checked_add(rank.checked_mul(hand.bid)?)
+        })
+        .ok_or(Error::NoAnswer("the total overflows u32"))
 }
 
-fn parse_draw(l: String) -> Hand {
-    let mut split = l.split(' ');
+fn parse_draw(i: usize, l: &[u8]) -> Result<Hand, ParseError> {
+    let Some((cards_str, bid_str)) = lines::split_once(l, b" ") else {
+        return Err(ParseError::new(DAY, i, l.len(), "", "bid"));
+    };
 
-    let cards = split
-        .next()
-        .expect("line should start with hand string")
-        .chars()
-        .collect::<Vec<char>>()
-        .try_into()
-        .expect("hand should be convertible into and array of 5 chars");
+    let cards = match cards_str.try_into() {
+        Ok(cards) => cards,
+        Err(_) => {
+            let found = String::from_utf8_lossy(cards_str);
+            return Err(ParseError::new(DAY, i, 0, found, "hand of 5 cards"));
+        }
+    };
+    let is_card = |c: &u8| matches!(c, b'2'..=b'9' | b'T' | b'J' | b'Q' | b'K' | b'A');
+    if let Some(j) = cards_str.iter().position(|c| !is_card(c)) {
+        return Err(ParseError::at(DAY, i, l, j, "card"));
+    }
 
-    let bid = split
-        .next()
-        .expect("line should end with bid string")
-        .parse()
-        .expect("bid string should be parseable as u32");
+    let Some(bid) = lines::number(bid_str) else {
+        let found = String::from_utf8_lossy(bid_str);
+        return Err(ParseError::new(DAY, i, cards_str.len() + 1, found, "bid"));
+    };
 
-    Hand(cards, bid)
+    Ok(Hand { cards, bid })
 }
 
 #[test]
-fn sample_input() {
+fn invalid_card() {
     let input = "\
         32T3K 765\n\
-        T55J5 684\n\
-        KK677 28\n\
-        KTJJT 220\n\
-        QQQJA 483\n\
+        T55X5 684\n\
         "
     .as_bytes();
-    assert_eq!(total(input), 6440)
+    match parse(input) {
+        Err(Error::Parse(e)) => assert_eq!(e, ParseError::new(DAY, 1, 3, "X", "card")),
+        r => panic!("unexpected result {r:?}"),
+    }
+}
+
+#[test]
+fn larger_than_puzzle_input() {
+    // Equal hands are ranked in their order of appearance.
+    let input = "23456 1\n".repeat(1500);
+    assert_eq!(
+        total(&parse(input.as_bytes()).unwrap()).unwrap(),
+        1500 * 1501 / 2
+    );
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn round_trip(
+        cards in proptest::array::uniform5(
+            proptest::sample::select(&b"23456789TJQKA"[..]),
+        ),
+        bid in proptest::num::u32::ANY,
+    ) {
+        let hand = parse_draw(0, format!("{} {bid}", String::from_utf8_lossy(&cards)).as_bytes());
+        let hand = hand.unwrap();
+        proptest::prop_assert_eq!(hand, Hand { cards, bid });
+    }
 }
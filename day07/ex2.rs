@@ -1,178 +1,110 @@
-use std::cmp::{Ord, Ordering};
+use adventofcode2023::day07::ex1::{self, Hand, HandType};
+use adventofcode2023::{input, Error};
 use std::collections::HashMap;
-use std::convert::Into;
-use std::fs::File;
-use std::io;
-use std::io::{BufRead, BufReader};
-use std::ops::ControlFlow;
-use std::time::Instant;
+use std::process::ExitCode;
+
+const DAY: u8 = 7;
 
 const HAND_SIZE: usize = 5;
-const MAX_HANDS: usize = 1000;
 
-fn main() -> io::Result<()> {
-    let f = File::open("day07/input")?;
-    let begin = Instant::now();
-    println!("{}", total(f));
-    println!("took: {:?}", Instant::now() - begin);
-    Ok(())
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
-enum HandType {
-    HighCard = 0,
-    OnePair = 1,
-    TwoPair = 2,
-    ThreeOfAKind = 3,
-    FullHouse = 4,
-    FourOfAKind = 5,
-    FiveOfAKind = 6,
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", total(&ex1::parse(f)?)?);
+    Ok(())
 }
 
-#[derive(PartialEq, Eq)]
-struct Hand([char; HAND_SIZE], u32); // cards, bid
+// Type of the hand, from how many of its cards are alike.
+fn hand_type(cards: &[u8; HAND_SIZE]) -> HandType {
+    let mut card_types = HashMap::with_capacity(cards.len());
+    for &c in cards {
+        card_types
+            .entry(c)
+            .and_modify(|n| *n += 1)
+            .or_insert(1usize);
+    }
 
-impl From<&Hand> for HandType {
-    fn from(h: &Hand) -> Self {
-        let mut card_types = HashMap::with_capacity(h.0.len());
-        for c in h.0 {
-            card_types
-                .entry(c)
-                .and_modify(|n| *n += 1)
-                .or_insert(1usize);
+    match card_types.len() {
+        1 => HandType::FiveOfAKind,
+        2 => {
+            if card_types.contains_key(&b'J') {
+                HandType::FiveOfAKind
+            } else if *card_types.values().max().unwrap() == 4 {
+                HandType::FourOfAKind
+            } else {
+                HandType::FullHouse
+            }
         }
-
-        match card_types.len() {
-            1 => HandType::FiveOfAKind,
-            2 => {
-                if card_types.contains_key(&'J') {
-                    HandType::FiveOfAKind
-                } else if *card_types.values().max().unwrap() == 4 {
+        3 => {
+            if *card_types.values().max().unwrap() == 3 {
+                if card_types.contains_key(&b'J') {
                     HandType::FourOfAKind
                 } else {
-                    HandType::FullHouse
-                }
-            }
-            3 => {
-                if *card_types.values().max().unwrap() == 3 {
-                    if card_types.contains_key(&'J') {
-                        HandType::FourOfAKind
-                    } else {
-                        HandType::ThreeOfAKind
-                    }
-                } else if let Some(&n) = card_types.get(&'J') {
-                    if n == 2 {
-                        HandType::FourOfAKind
-                    } else {
-                        HandType::FullHouse
-                    }
-                } else {
-                    HandType::TwoPair
-                }
-            }
-            4 => {
-                if card_types.contains_key(&'J') {
                     HandType::ThreeOfAKind
-                } else {
-                    HandType::OnePair
                 }
-            }
-            _ => {
-                if card_types.contains_key(&'J') {
-                    HandType::OnePair
+            } else if let Some(&n) = card_types.get(&b'J') {
+                if n == 2 {
+                    HandType::FourOfAKind
                 } else {
-                    HandType::HighCard
+                    HandType::FullHouse
                 }
+            } else {
+                HandType::TwoPair
             }
         }
-    }
-}
-
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let self_type: HandType = self.into();
-        let other_type: HandType = other.into();
-
-        match self_type.cmp(&other_type) {
-            Ordering::Equal => {
-                let cmp = self.0.into_iter().zip(other.0).try_for_each(|cards| {
-                    fn card_value(c: char) -> u8 {
-                        match c {
-                            'J' => 0,
-                            '2'..='9' => c as u8 - b'1',
-                            'T' => 9,
-                            'Q' => 11,
-                            'K' => 12,
-                            'A' => 13,
-                            _ => u8::MAX,
-                        }
-                    }
-                    match card_value(cards.0).cmp(&card_value(cards.1)) {
-                        Ordering::Equal => ControlFlow::Continue(()),
-                        o => ControlFlow::Break(o),
-                    }
-                });
-                if let ControlFlow::Break(cmp) = cmp {
-                    cmp
-                } else {
-                    Ordering::Equal
-                }
+        4 => {
+            if card_types.contains_key(&b'J') {
+                HandType::ThreeOfAKind
+            } else {
+                HandType::OnePair
+            }
+        }
+        _ => {
+            if card_types.contains_key(&b'J') {
+                HandType::OnePair
+            } else {
+                HandType::HighCard
             }
-            o => o,
         }
     }
 }
 
-fn total<R: io::Read>(r: R) -> u32 {
-    let mut lines = BufReader::new(r).lines();
+// Strength of the hand: its type, then the values of its cards in order.
+fn strength(hand: &Hand) -> (HandType, [u8; HAND_SIZE]) {
+    (hand_type(&hand.cards), hand.cards.map(card_value))
+}
 
-    let mut hands = Vec::with_capacity(MAX_HANDS);
-    while let Some(Ok(l)) = lines.next() {
-        hands.push(parse_draw(l));
+fn card_value(c: u8) -> u8 {
+    match c {
+        b'J' => 0,
+        b'2'..=b'9' => c - b'1',
+        b'T' => 9,
+        b'Q' => 11,
+        b'K' => 12,
+        b'A' => 13,
+        _ => u8::MAX,
     }
-    hands.sort();
-
-    hands
-        .iter()
-        .enumerate()
-        .fold(0, |acc, e| acc + (e.0 as u32 + 1) * e.1 .1)
 }
 
-fn parse_draw(l: String) -> Hand {
-    let mut split = l.split(' ');
-
-    let cards = split
-        .next()
-        .expect("line should start with hand string")
-        .chars()
-        .collect::<Vec<char>>()
-        .try_into()
-        .expect("hand should be convertible into and array of 5 chars");
-
-    let bid = split
-        .next()
-        .expect("line should end with bid string")
-        .parse()
-        .expect("bid string should be parseable as u32");
-
-    Hand(cards, bid)
+pub fn total(hands: &[Hand]) -> Result<u32, Error> {
+    ex1::winnings(hands, strength)
 }
 
 #[test]
-fn sample_input() {
-    let input = "\
-        32T3K 765\n\
-        T55J5 684\n\
-        KK677 28\n\
-        KTJJT 220\n\
-        QQQJA 483\n\
-        "
-    .as_bytes();
-    assert_eq!(total(input), 5905)
+fn larger_than_puzzle_input() {
+    // Equal hands are ranked in their order of appearance.
+    let input = "23456 1\n".repeat(1500);
+    assert_eq!(
+        total(&ex1::parse(input.as_bytes()).unwrap()).unwrap(),
+        1500 * 1501 / 2
+    );
 }
@@ -1,101 +1,232 @@
+use adventofcode2023::lines::{self, LineSource, Lines};
+use adventofcode2023::{input, Error, ParseError};
 use std::collections::HashMap;
-use std::fs::File;
 use std::io;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
-
-fn main() -> io::Result<()> {
-    let f = File::open("day08/input")?;
-    let begin = Instant::now();
-    println!("{}", steps(f));
-    println!("took: {:?}", Instant::now() - begin);
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", steps(&parse(f)?)?);
     Ok(())
 }
 
+const DAY: u8 = 8;
+
+// Capacity hints, from the shape of the puzzle input.
 const MAX_NODES: usize = 790;
 const NODE_CHARS: usize = 3;
+const LEFT_OFFSET: usize = "AAA = (".len();
+const RIGHT_OFFSET: usize = "AAA = (BBB, ".len();
+
+pub type Network = HashMap<String, (String, String, usize)>; // left, right, line
 
-fn steps<R: io::Read>(r: R) -> u32 {
-    let mut lines = BufReader::new(r).lines();
+/// Instructions, each b'L' or b'R', and the network of nodes they lead through.
+pub struct Map {
+    pub instructions: Vec<u8>,
+    pub network: Network,
+    pub nodes: Vec<String>, // in the order of their lines
+}
+
+pub fn parse<R: io::Read>(r: R) -> Result<Map, Error> {
+    parse_lines(Lines::new(r))
+}
+
+// Same as `parse`, for an input held in memory.
+pub fn parse_bytes(input: &[u8]) -> Result<Map, Error> {
+    parse_lines(lines::split(input))
+}
 
-    let binding = lines.next().unwrap().unwrap();
-    let mut steps = binding.chars().cycle();
+fn parse_lines(mut lines: impl LineSource) -> Result<Map, Error> {
+    let instructions = lines
+        .next_line()
+        .transpose()?
+        .map_or_else(Vec::new, |(_, l)| l.to_vec());
+    if let Some(j) = instructions.iter().position(|&c| c != b'L' && c != b'R') {
+        return Err(ParseError::at(DAY, 0, &instructions, j, "'L' or 'R'").into());
+    } else if instructions.is_empty() {
+        return Err(ParseError::new(DAY, 0, 0, "", "'L' or 'R'").into());
+    }
 
-    lines.next();
+    if let Some(l) = lines.next_line() {
+        let (i, l) = l?;
+        if !l.is_empty() {
+            return Err(ParseError::at(DAY, i, l, 0, "empty line").into());
+        }
+    }
 
     let mut network = HashMap::with_capacity(MAX_NODES);
-    for l in lines {
-        match l {
-            Ok(l) => {
-                let e = parse_network_entry(l);
-                network.insert(e.0, e.1);
-            }
-            Err(e) => panic!("{e}"),
+    let mut nodes = Vec::with_capacity(MAX_NODES);
+    while let Some(l) = lines.next_line() {
+        let (i, l) = l?;
+        let e = parse_network_entry(i, l)?;
+        if network.contains_key(&e.0) {
+            return Err(ParseError::new(DAY, i, 0, e.0, "node defined once").into());
         }
+        nodes.push(e.0.clone());
+        network.insert(e.0, (e.1 .0, e.1 .1, i));
     }
 
-    let mut n_steps = 0u32;
+    Ok(Map {
+        instructions,
+        network,
+        nodes,
+    })
+}
+
+pub fn steps(map: &Map) -> Result<u32, Error> {
+    let network = &map.network;
+    let mut steps = map.instructions.iter().cycle();
+
+    // The bound below on the steps may not fit in u32.
+    let mut n_steps = 0u64;
     let mut next_step = "AAA";
+    let mut referenced_at = (0, 0); // line, offset
     loop {
         if next_step == "ZZZ" {
-            break n_steps;
+            break u32::try_from(n_steps).map_err(|_| Error::NoAnswer("too many steps"));
+        }
+        let Some(entry) = network.get(next_step) else {
+            let (line, offset) = referenced_at;
+            return Err(ParseError::new(DAY, line, offset, next_step, "known node").into());
+        };
+        // Past this many steps, a state (node, instruction) repeats and ZZZ is never reached.
+        if n_steps >= network.len() as u64 * map.instructions.len() as u64 {
+            return Err(Error::NoAnswer("ZZZ cannot be reached from AAA"));
         }
         n_steps += 1;
         if let Some(s) = steps.next() {
-            next_step = match s {
-                'L' => &network.get(next_step).unwrap().0,
-                'R' => &network.get(next_step).unwrap().1,
-                _ => panic!("should be either L or R"),
+            (next_step, referenced_at) = match s {
+                b'L' => (&entry.0, (entry.2, LEFT_OFFSET)),
+                _ => (&entry.1, (entry.2, RIGHT_OFFSET)),
             }
         }
     }
 }
 
-fn parse_network_entry(l: String) -> (String, (String, String)) {
-    let mut chars = l.chars();
+fn parse_network_entry(i: usize, l: &[u8]) -> Result<(String, (String, String)), ParseError> {
+    let mut j = 0; // byte of `l` to parse next
 
-    let mut node = String::with_capacity(NODE_CHARS);
-    (0..3).for_each(|_| node.push(chars.next().unwrap()));
+    let node = parse_node(i, l, &mut j)?;
+    skip(i, l, &mut j, " = (")?;
+    let left = parse_node(i, l, &mut j)?;
+    skip(i, l, &mut j, ", ")?;
+    let right = parse_node(i, l, &mut j)?;
+    skip(i, l, &mut j, ")")?;
 
-    chars.nth(" = (".len() - 1);
-
-    let mut left = String::with_capacity(NODE_CHARS);
-    (0..3).for_each(|_| left.push(chars.next().unwrap()));
+    if j < l.len() {
+        return Err(ParseError::at(DAY, i, l, j, "end of line"));
+    }
 
-    chars.nth(", ".len() - 1);
+    Ok((node, (left, right)))
+}
 
-    let mut right = String::with_capacity(NODE_CHARS);
-    (0..3).for_each(|_| right.push(chars.next().unwrap()));
+fn parse_node(i: usize, l: &[u8], j: &mut usize) -> Result<String, ParseError> {
+    let mut node = String::with_capacity(NODE_CHARS);
+    for _ in 0..NODE_CHARS {
+        match l.get(*j) {
+            Some(&b) if b.is_ascii_alphanumeric() => node.push(char::from(b)),
+            _ => return Err(ParseError::at(DAY, i, l, *j, "node")),
+        }
+        *j += 1;
+    }
+    Ok(node)
+}
 
-    (node, (left, right))
+fn skip(i: usize, l: &[u8], j: &mut usize, sep: &'static str) -> Result<(), ParseError> {
+    for &s in sep.as_bytes() {
+        if l.get(*j) != Some(&s) {
+            return Err(ParseError::at(DAY, i, l, *j, sep));
+        }
+        *j += 1;
+    }
+    Ok(())
 }
 
 #[test]
-fn sample_input1() {
+fn unknown_node() {
     let input = "\
-        RL\n\
+        LLR\n\
         \n\
-        AAA = (BBB, CCC)\n\
-        BBB = (DDD, EEE)\n\
-        CCC = (ZZZ, GGG)\n\
-        DDD = (DDD, DDD)\n\
-        EEE = (EEE, EEE)\n\
-        GGG = (GGG, GGG)\n\
+        AAA = (BBB, BBB)\n\
+        BBB = (AAA, ZZY)\n\
         ZZZ = (ZZZ, ZZZ)\n\
         "
     .as_bytes();
-    assert_eq!(steps(input), 2)
+    match steps(&parse(input).unwrap()) {
+        Err(Error::Parse(e)) => assert_eq!(e, ParseError::new(DAY, 3, 12, "ZZY", "known node")),
+        r => panic!("unexpected result {r:?}"),
+    }
 }
 
 #[test]
-fn sample_input2() {
+fn malformed_network() {
+    let error = |input: &str| match parse(input.as_bytes()) {
+        Err(Error::Parse(e)) => e,
+        r => panic!("unexpected result {:?}", r.map(|m| m.nodes)),
+    };
+    assert_eq!(
+        error("L\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n"),
+        ParseError::new(DAY, 1, 0, "A", "empty line")
+    );
+    assert_eq!(
+        error("L\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\nAAA = (AAA, AAA)\n"),
+        ParseError::new(DAY, 4, 0, "AAA", "node defined once")
+    );
+}
+
+#[test]
+fn larger_than_puzzle_input() {
+    // A chain of 1000 nodes from AAA to ZZZ, named with letters from B to Y.
+    let name = |k: u32| {
+        (0..3)
+            .map(|d| char::from(b'B' + (k / 24u32.pow(d) % 24) as u8))
+            .collect::<String>()
+    };
+    let mut input = format!("L\n\nAAA = ({}, AAA)\n", name(0));
+    for k in 0..1000 {
+        let next = if k == 999 {
+            "ZZZ".to_string()
+        } else {
+            name(k + 1)
+        };
+        input += &format!("{} = ({next}, {next})\n", name(k));
+    }
+    input += "ZZZ = (ZZZ, ZZZ)\n";
+    assert_eq!(steps(&parse(input.as_bytes()).unwrap()).unwrap(), 1001);
+}
+
+#[test]
+fn unreachable_end() {
     let input = "\
-        LLR\n\
+        L\n\
         \n\
         AAA = (BBB, BBB)\n\
         BBB = (AAA, ZZZ)\n\
         ZZZ = (ZZZ, ZZZ)\n\
         "
     .as_bytes();
-    assert_eq!(steps(input), 6)
+    assert!(matches!(
+        steps(&parse(input).unwrap()),
+        Err(Error::NoAnswer(_))
+    ));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn round_trip(node in "[0-9A-Z]{3}", left in "[0-9A-Z]{3}", right in "[0-9A-Z]{3}") {
+        let entry = parse_network_entry(2, format!("{node} = ({left}, {right})").as_bytes());
+        let entry = entry.unwrap();
+        proptest::prop_assert_eq!(entry, (node, (left, right)));
+    }
 }
@@ -1,105 +1,261 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io;
-use std::io::{BufRead, BufReader};
-use std::time::Instant;
-
-fn main() -> io::Result<()> {
-    let f = File::open("day08/input")?;
-    let begin = Instant::now();
-    println!("{}", steps(f));
-    println!("took: {:?}", Instant::now() - begin);
+use adventofcode2023::day08::ex1::{self, Map, Network};
+use adventofcode2023::{input, Error, ParseError};
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", steps(&ex1::parse(f)?)?);
     Ok(())
 }
 
-const MAX_NODES: usize = 790;
+const DAY: u8 = 8;
+
+// Capacity hints, from the shape of the puzzle input.
 const MAX_START_NODES: usize = 6;
-const NODE_CHARS: usize = 3;
+const LEFT_OFFSET: usize = "AAA = (".len();
+const RIGHT_OFFSET: usize = "AAA = (BBB, ".len();
+// Bound on the residues combined below, whose count is the product of the steps at nodes ending
+// with 'Z' in every cycle.
+const MAX_RESIDUES: usize = 1 << 20;
 
-fn steps<R: io::Read>(r: R) -> u32 {
-    let mut lines = BufReader::new(r).lines();
+pub fn steps(map: &Map) -> Result<u64, Error> {
+    let (network, instructions) = (&map.network, &map.instructions);
+    let mut start_nodes = Vec::with_capacity(MAX_START_NODES);
+    start_nodes.extend(map.nodes.iter().filter(|node| node.ends_with('A')));
 
-    let binding = lines.next().unwrap().unwrap();
-    let mut steps = binding.chars().cycle();
+    if start_nodes.is_empty() {
+        return Err(Error::NoAnswer("no node ends with 'A'"));
+    }
+    let mut walks = start_nodes
+        .iter()
+        .map(|node| walk(network, instructions, node))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    lines.next();
+    // All the ghosts are in their cycle from this step on. Steps up to a whole cycle later are
+    // checked directly, which finds the answer of inputs whose cycles end on most nodes without
+    // combining their many residues.
+    let cycling = walks.iter().map(|w| w.start).max().unwrap_or(0);
+    let shortest = walks.iter().map(|w| w.period).min().unwrap_or(0);
+    if let Some(t) = (1..cycling + shortest).find(|&t| walks.iter().all(|w| w.at_end(t))) {
+        return u64::try_from(t).map_err(|_| Error::NoAnswer("too many steps"));
+    }
 
-    let mut start_nodes = Vec::with_capacity(MAX_START_NODES);
-    let mut network = HashMap::with_capacity(MAX_NODES);
-    for l in lines {
-        match l {
-            Ok(l) => {
-                let e = parse_network_entry(l);
-                if e.0.ends_with('A') {
-                    start_nodes.push(e.0.clone());
+    // Steps at which all ghosts are at an end of their cycle, as residues modulo a period. Cycles
+    // with the fewest ends are combined first, so that the residues grow as late as possible.
+    walks.sort_by_key(|w| w.cycle.len());
+    let mut common = vec![(0, 1)];
+    for w in &walks {
+        let mut next =
+            Vec::with_capacity(common.len().saturating_mul(w.cycle.len()).min(MAX_RESIDUES));
+        for &(r, m) in &common {
+            for &z in &w.cycle {
+                if let Some(c) = crt((r, m), (z % w.period, w.period))? {
+                    if next.len() == MAX_RESIDUES {
+                        return Err(Error::NoAnswer("too many candidate steps"));
+                    }
+                    next.push(c);
                 }
-                network.insert(e.0, e.1);
             }
-            Err(e) => panic!("{e}"),
         }
+        next.sort_unstable();
+        next.dedup();
+        common = next;
     }
+    let first = common
+        .into_iter()
+        .map(|(r, m)| {
+            // First step congruent to r from `cycling` on.
+            let t = cycling.max(1);
+            let d = if r >= t % m {
+                r - t % m
+            } else {
+                r + (m - t % m)
+            };
+            t.checked_add(d)
+        })
+        .min()
+        .ok_or(Error::NoAnswer(
+            "the ghosts are never all on nodes ending with 'Z'",
+        ))?;
+    first
+        .and_then(|t| u64::try_from(t).ok())
+        .ok_or(Error::NoAnswer("too many steps"))
+}
+
+// Path of a single ghost, which ends up repeating a cycle since the network is finite.
+struct Walk {
+    tail: Vec<u128>,  // steps at nodes ending with 'Z', before the cycle, in order
+    start: u128,      // first step of the cycle
+    period: u128,     // length of the cycle
+    cycle: Vec<u128>, // steps at nodes ending with 'Z', in the first cycle, in order
+}
 
-    let mut n_steps = 0u32;
-    let mut cur_last_chars = HashSet::with_capacity(start_nodes.len());
-    let mut cur_nodes = start_nodes;
-    //println!("{:?} {:?}", cur_nodes, cur_last_chars);
-    loop {
-        if cur_last_chars.len() == 1 && cur_last_chars.contains(&'Z') {
-            break n_steps;
+impl Walk {
+    fn at_end(&self, t: u128) -> bool {
+        if t < self.start {
+            self.tail.binary_search(&t).is_ok()
+        } else {
+            let t = self.start + (t - self.start) % self.period;
+            self.cycle.binary_search(&t).is_ok()
         }
-        cur_last_chars.clear();
-        n_steps += 1;
-        if let Some(s) = steps.next() {
-            cur_nodes = cur_nodes
-                .into_iter()
-                .map(|node| {
-                    let next_node = match s {
-                        'L' => &network.get(&node).unwrap().0,
-                        'R' => &network.get(&node).unwrap().1,
-                        _ => panic!("should be either L or R"),
-                    };
-                    cur_last_chars.insert(next_node.chars().last().unwrap());
-                    next_node.clone()
-                })
-                .collect();
-            //println!("{s} {:?} {:?}", cur_nodes, cur_last_chars);
-        };
     }
 }
 
-fn parse_network_entry(l: String) -> (String, (String, String)) {
-    let mut chars = l.chars();
+fn walk(network: &Network, instructions: &[u8], start: &str) -> Result<Walk, Error> {
+    let mut seen = HashMap::new();
+    let mut ends = Vec::new();
+    let mut node = start;
+    for t in 0u128.. {
+        let idx = (t % instructions.len() as u128) as usize;
+        if let Some(&first) = seen.get(&(node, idx)) {
+            let (tail, cycle) = ends.iter().partition(|&&z| z < first);
+            return Ok(Walk {
+                tail,
+                start: first,
+                period: t - first,
+                cycle,
+            });
+        }
+        seen.insert((node, idx), t);
+        if t > 0 && node.ends_with('Z') {
+            ends.push(t);
+        }
 
-    let mut node = String::with_capacity(NODE_CHARS);
-    (0..3).for_each(|_| node.push(chars.next().unwrap()));
+        // current nodes are always part of the network
+        let entry = &network[node];
+        let (next, offset) = match instructions[idx] {
+            b'L' => (&entry.0, LEFT_OFFSET),
+            _ => (&entry.1, RIGHT_OFFSET),
+        };
+        if !network.contains_key(next) {
+            return Err(ParseError::new(DAY, entry.2, offset, next.as_str(), "known node").into());
+        }
+        node = next;
+    }
+    unreachable!("the network has a finite number of states")
+}
 
-    chars.nth(" = (".len() - 1);
+// Solution of both congruences, if any, using the Chinese remainder theorem.
+fn crt((r1, m1): (u128, u128), (r2, m2): (u128, u128)) -> Result<Option<(u128, u128)>, Error> {
+    let overflow = || Error::NoAnswer("too many steps");
+    let g = gcd(m1, m2);
+    let diff = (r2 + m2 - r1 % m2) % m2;
+    if diff % g != 0 {
+        return Ok(None);
+    }
+    let m = (m1 / g).checked_mul(m2).ok_or_else(overflow)?;
+    // r1 + m1 * k, where k is a solution of m1 * k = diff (mod m2)
+    let (n1, n2) = (m1 / g, m2 / g);
+    let k = (diff / g) % n2 * inverse(n1 % n2, n2) % n2;
+    let r = (r1 + m1.checked_mul(k).ok_or_else(overflow)?) % m;
+    Ok(Some((r, m)))
+}
 
-    let mut left = String::with_capacity(NODE_CHARS);
-    (0..3).for_each(|_| left.push(chars.next().unwrap()));
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
 
-    chars.nth(", ".len() - 1);
+// Inverse of a modulo m, for a and m coprime.
+fn inverse(a: u128, m: u128) -> u128 {
+    let (mut old_r, mut r) = (a as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    old_s.rem_euclid(m as i128) as u128
+}
 
-    let mut right = String::with_capacity(NODE_CHARS);
-    (0..3).for_each(|_| right.push(chars.next().unwrap()));
+#[test]
+fn larger_than_puzzle_input() {
+    // A chain of 1000 nodes from AAA to ZZZ, named with letters from B to Y.
+    let name = |k: u32| {
+        (0..3)
+            .map(|d| char::from(b'B' + (k / 24u32.pow(d) % 24) as u8))
+            .collect::<String>()
+    };
+    let mut input = format!("L\n\nAAA = ({}, AAA)\n", name(0));
+    for k in 0..1000 {
+        let next = if k == 999 {
+            "ZZZ".to_string()
+        } else {
+            name(k + 1)
+        };
+        input += &format!("{} = ({next}, {next})\n", name(k));
+    }
+    input += "ZZZ = (ZZZ, ZZZ)\n";
+    assert_eq!(steps(&ex1::parse(input.as_bytes()).unwrap()).unwrap(), 1001);
+}
 
-    (node, (left, right))
+#[test]
+fn cycles_ending_on_most_nodes() {
+    // Ghosts on cycles of coprime lengths whose nodes all end with 'Z', but for their start and
+    // one other node. Their residues would combine into billions of candidate steps.
+    let mut input = "L\n\n".to_string();
+    let name = |g: usize, j: usize, len: usize| {
+        let k = g * 100 + j;
+        let end = match j {
+            0 => 'A',
+            _ if j == len / 2 => 'B',
+            _ => 'Z',
+        };
+        format!(
+            "{}{}{end}",
+            char::from(b'A' + (k / 26) as u8),
+            char::from(b'A' + (k % 26) as u8)
+        )
+    };
+    for (g, len) in [97, 89, 83, 79, 73].into_iter().enumerate() {
+        for j in 0..len {
+            let next = name(g, (j + 1) % len, len);
+            input += &format!("{} = ({next}, {next})\n", name(g, j, len));
+        }
+    }
+    assert_eq!(steps(&ex1::parse(input.as_bytes()).unwrap()).unwrap(), 1);
 }
 
 #[test]
-fn sample_input() {
-    let input = "\
-        LR\n\
-        \n\
-        11A = (11B, XXX)\n\
-        11B = (XXX, 11Z)\n\
-        11Z = (11B, XXX)\n\
-        22A = (22B, XXX)\n\
-        22B = (22C, 22C)\n\
-        22C = (22Z, 22Z)\n\
-        22Z = (22B, 22B)\n\
-        XXX = (XXX, XXX)\n\
-        "
-    .as_bytes();
-    assert_eq!(steps(input), 6)
+fn answer_beyond_u32() {
+    // Ghosts walking chains of prime lengths, from their start to their end and back to the
+    // second node, which are first all at their ends after the product of the lengths.
+    const CHARS: &[u8] = b"0123456789BCDEFGHIJKLMNOPQRSTUVWXY";
+    let mut input = "L\n\n".to_string();
+    let name = |g: usize, j: usize, len: usize| {
+        let k = g * 3000 + j;
+        let end = match j {
+            0 => 'A',
+            _ if j == len => 'Z',
+            _ => char::from(CHARS[k % 34]),
+        };
+        format!(
+            "{}{}{end}",
+            char::from(CHARS[k / 34 / 34]),
+            char::from(CHARS[k / 34 % 34])
+        )
+    };
+    for (g, len) in [2003, 2011, 2017].into_iter().enumerate() {
+        for j in 0..=len {
+            let next = name(g, if j == len { 1 } else { j + 1 }, len);
+            input += &format!("{} = ({next}, {next})\n", name(g, j, len));
+        }
+    }
+    let answer = steps(&ex1::parse(input.as_bytes()).unwrap()).unwrap();
+    assert_eq!(answer, 2003 * 2011 * 2017);
+    assert!(answer > u32::MAX.into());
 }
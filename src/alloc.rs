@@ -0,0 +1,98 @@
+//! Opt-in counting of heap allocations, enabled with the `count-allocations` feature:
+//!
+//! ```sh
+//! cargo run --release --features count-allocations --bin aoc -- run --all
+//! ```
+//!
+//! Counters are kept per thread, so that solvers run in parallel are measured separately.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+pub const ENABLED: bool = cfg!(feature = "count-allocations");
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Allocator forwarding to the system one, while counting on the current thread.
+pub struct Counting;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub allocations: u64,
+    pub bytes: u64, // allocated in total, freed or not
+    pub peak: u64,  // maximum of the bytes live at once
+}
+
+thread_local! {
+    // Constant initialization without destructor, so that it never allocates itself.
+    static STATS: Cell<Stats> = const { Cell::new(Stats { allocations: 0, bytes: 0, peak: 0 }) };
+    static LIVE: Cell<i64> = const { Cell::new(0) };
+}
+
+fn record(allocated: usize, freed: usize) {
+    // Fails during the destruction of the thread, whose allocations do not matter.
+    let _ = LIVE.try_with(|live| {
+        let now = live.get() + allocated as i64 - freed as i64;
+        live.set(now);
+        STATS.with(|s| {
+            let mut stats = s.get();
+            if allocated > 0 {
+                stats.allocations += 1;
+                stats.bytes += allocated as u64;
+            }
+            stats.peak = stats.peak.max(now.max(0) as u64);
+            s.set(stats);
+        });
+    });
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size(), 0);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size(), 0);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(0, layout.size());
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size, layout.size());
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Runs `f` and returns the allocations it made on the current thread, or `None` when counting is
+/// not enabled.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<Stats>) {
+    if !ENABLED {
+        return (f(), None);
+    }
+    LIVE.with(|live| live.set(0));
+    STATS.with(|s| s.set(Stats::default()));
+    let r = f();
+    (r, Some(STATS.with(Cell::get)))
+}
+
+#[cfg(feature = "count-allocations")]
+#[test]
+fn count_allocations() {
+    let (_, stats) = measure(|| {
+        let mut v = Vec::<u8>::with_capacity(100);
+        v.extend_from_slice(&[0; 300]); // reallocation
+        drop(v);
+        vec![0u8; 50]
+    });
+    let stats = stats.unwrap();
+    assert_eq!(stats.allocations, 3);
+    assert_eq!(stats.bytes, 450);
+    assert_eq!(stats.peak, 300);
+}
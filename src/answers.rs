@@ -0,0 +1,123 @@
+//! Verified answers to the puzzles, recorded in an `answers.toml` file next to the inputs so that
+//! refactorings cannot silently change them:
+//!
+//! ```toml
+//! [[day05]]              # one table per input file
+//! part1 = 35
+//! part2 = 46
+//!
+//! [[day05]]
+//! input = "alt/day05"    # relative to answers.toml, defaults to the day's input
+//! part1 = 52
+//! ```
+
+use crate::input::{self, Source};
+use crate::registry::Solver;
+use crate::{toml, Error};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const FILE: &str = "answers.toml";
+
+pub struct Answer {
+    pub day: u8,
+    pub part: u8,
+    pub input: Source,
+    pub expected: String,
+}
+
+pub enum Outcome {
+    Pass,
+    Fail { found: String },
+    Error(Error),
+}
+
+impl Answer {
+    /// Runs the solver of the same day and part on the input of the answer.
+    pub fn check(&self, s: &Solver) -> Outcome {
+        let run = self
+            .input
+            .open()
+            .map_err(Error::from)
+            .and_then(|mut r| s.solve(&mut r));
+        match run {
+            Ok(run) if run.answer == self.expected => Outcome::Pass,
+            Ok(run) => Outcome::Fail { found: run.answer },
+            Err(e) => Outcome::Error(e),
+        }
+    }
+}
+
+/// Default location of the answers: in the input directory.
+pub fn path() -> PathBuf {
+    input::dir().join(FILE)
+}
+
+pub fn load(path: &Path) -> io::Result<Vec<Answer>> {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    parse(&fs::read_to_string(path)?, dir)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Parses the answers, with input paths relative to `dir`.
+pub fn parse(s: &str, dir: &Path) -> Result<Vec<Answer>, toml::Error> {
+    let mut answers = Vec::new();
+    for t in toml::parse(s)? {
+        let err = |expected| toml::Error {
+            line: t.line,
+            expected,
+        };
+        if t.name.is_empty() && t.entries.is_empty() {
+            continue;
+        }
+        let day = match t.name.strip_prefix("day").map(str::parse) {
+            Some(Ok(day)) if t.array => day,
+            _ => return Err(err("[[dayNN]] table")),
+        };
+
+        let input = match t.get("input") {
+            None => Source::resolve(day, None),
+            Some(toml::Value::String(p)) => Source::File(dir.join(p)),
+            Some(_) => return Err(err("input path string")),
+        };
+        for (k, v) in &t.entries {
+            let part = match k.as_str() {
+                "input" => continue,
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(err("only input, part1 and part2 keys")),
+            };
+            answers.push(Answer {
+                day,
+                part,
+                input: input.clone(),
+                expected: v.to_string(),
+            });
+        }
+    }
+    Ok(answers)
+}
+
+#[test]
+fn parse_answers() {
+    let doc = "\
+        [[day05]]\n\
+        part1 = 35\n\
+        part2 = \"46\"\n\
+        \n\
+        [[day05]]\n\
+        input = \"alt/day05\"\n\
+        part1 = 52\n\
+        ";
+    let answers = parse(doc, Path::new("/aoc")).unwrap();
+    let summary = answers
+        .iter()
+        .map(|a| (a.day, a.part, a.expected.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(summary, [(5, 1, "35"), (5, 2, "46"), (5, 1, "52")]);
+    assert!(matches!(&answers[2].input, Source::File(p) if p.as_os_str() == "/aoc/alt/day05"));
+
+    let err = parse("[day05]\npart1 = 35\n", Path::new("")).err();
+    assert_eq!(err.map(|e| e.line), Some(1));
+}
@@ -0,0 +1,182 @@
+//! Multi-pattern matcher in the manner of Aho–Corasick: the patterns are compiled into a single
+//! automaton which finds all their occurrences, overlapping ones included, in one pass over the
+//! text. Adding a pattern only means adding it to the table the automaton is built from.
+
+pub struct Automaton<T> {
+    next: Vec<[u32; 256]>,     // transitions of every state, failures resolved
+    outputs: Vec<Vec<usize>>,  // patterns ending at every state, longest first
+    patterns: Vec<(usize, T)>, // length and value of every pattern
+    max_len: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Match<T> {
+    pub pattern: usize, // index in the table
+    pub start: usize,
+    pub end: usize,
+    pub value: T,
+}
+
+const NONE: u32 = u32::MAX;
+
+impl<T: Copy> Automaton<T> {
+    /// Compiles the patterns and their values. Empty patterns never match.
+    pub fn new<P: AsRef<[u8]>>(table: impl IntoIterator<Item = (P, T)>) -> Self {
+        let mut a = Automaton {
+            next: vec![[NONE; 256]],
+            outputs: vec![Vec::new()],
+            patterns: Vec::new(),
+            max_len: 0,
+        };
+        // trie of the patterns
+        for (p, value) in table {
+            let p = p.as_ref();
+            a.patterns.push((p.len(), value));
+            a.max_len = a.max_len.max(p.len());
+            if p.is_empty() {
+                continue;
+            }
+            let mut state = 0;
+            for &b in p {
+                if a.next[state][usize::from(b)] == NONE {
+                    a.next[state][usize::from(b)] = a.next.len() as u32;
+                    a.next.push([NONE; 256]);
+                    a.outputs.push(Vec::new());
+                }
+                state = a.next[state][usize::from(b)] as usize;
+            }
+            a.outputs[state].push(a.patterns.len() - 1);
+        }
+
+        // Breadth-first, so that the failure state of a state, which is shallower, is complete
+        // when the state is reached. Missing transitions become those of the failure state.
+        let mut fail = vec![0; a.next.len()];
+        let mut queue = std::collections::VecDeque::from([0]);
+        while let Some(s) = queue.pop_front() {
+            for b in 0..256 {
+                let t = a.next[s][b];
+                let fallback = if s == 0 { 0 } else { a.next[fail[s]][b] };
+                if t == NONE {
+                    a.next[s][b] = fallback;
+                    continue;
+                }
+                let t = t as usize;
+                fail[t] = fallback as usize;
+                // Patterns which are suffixes of this one end at the same time.
+                let suffixes = a.outputs[fail[t]].clone();
+                a.outputs[t].extend(suffixes);
+                queue.push_back(t);
+            }
+        }
+        a
+    }
+
+    /// Occurrence starting first in the text, the longest among those starting at the same
+    /// position. The text is only read up to the end of the longest pattern which could start
+    /// there, so that searching a reversed automaton in the reversed text finds the occurrence
+    /// ending last.
+    pub fn first_match(&self, text: impl IntoIterator<Item = u8>) -> Option<Match<T>> {
+        let mut first: Option<Match<T>> = None;
+        let mut state = 0;
+        for (i, b) in text.into_iter().enumerate() {
+            let end = i + 1;
+            // Occurrences ending from here on start after the first one.
+            if first.is_some_and(|m| end > m.start + self.max_len) {
+                break;
+            }
+            state = self.next[state][usize::from(b)] as usize;
+            for &pattern in &self.outputs[state] {
+                let (len, value) = self.patterns[pattern];
+                let start = end - len;
+                if first.is_none_or(|m| start < m.start || start == m.start && end > m.end) {
+                    first = Some(Match {
+                        pattern,
+                        start,
+                        end,
+                        value,
+                    });
+                }
+            }
+        }
+        first
+    }
+
+    /// All the occurrences of the patterns in the text, by end position, the longest first among
+    /// those ending at the same position.
+    pub fn find_overlapping<'a>(&'a self, text: &'a [u8]) -> Matches<'a, T> {
+        Matches {
+            automaton: self,
+            text,
+            state: 0,
+            end: 0,
+            pending: &[],
+        }
+    }
+}
+
+pub struct Matches<'a, T> {
+    automaton: &'a Automaton<T>,
+    text: &'a [u8],
+    state: usize,
+    end: usize,           // of the text read so far
+    pending: &'a [usize], // patterns ending at `end` which are still to be returned
+}
+
+impl<T: Copy> Iterator for Matches<'_, T> {
+    type Item = Match<T>;
+
+    fn next(&mut self) -> Option<Match<T>> {
+        while self.pending.is_empty() {
+            let &b = self.text.get(self.end)?;
+            self.state = self.automaton.next[self.state][usize::from(b)] as usize;
+            self.end += 1;
+            self.pending = &self.automaton.outputs[self.state];
+        }
+        let pattern = self.pending[0];
+        self.pending = &self.pending[1..];
+        let (len, value) = self.automaton.patterns[pattern];
+        Some(Match {
+            pattern,
+            start: self.end - len,
+            end: self.end,
+            value,
+        })
+    }
+}
+
+#[test]
+fn overlapping_matches() {
+    let a = Automaton::new([
+        ("eight", 8),
+        ("two", 2),
+        ("sept", 7),
+        ("dix-sept", 17),
+        ("", 0),
+    ]);
+    let found = |text: &str| {
+        a.find_overlapping(text.as_bytes())
+            .map(|m| (m.start, m.value))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(found("xeightwox"), [(1, 8), (5, 2)]);
+    assert_eq!(
+        found("dix-septeighteight"),
+        [(0, 17), (4, 7), (8, 8), (13, 8)]
+    );
+    assert_eq!(found("eigh"), []);
+}
+
+#[test]
+fn first_match() {
+    let table = [("seven", 7), ("seventeen", 17), ("teen", 0), ("nine", 9)];
+    let a = Automaton::new(table);
+    let first = |text: &str| a.first_match(text.bytes()).map(|m| (m.start, m.value));
+    assert_eq!(first("xseventeenine"), Some((1, 17)));
+    assert_eq!(first("sevenine"), Some((0, 7)));
+    assert_eq!(first("none"), None);
+
+    let reversed = Automaton::new(table.map(|(p, v)| (p.bytes().rev().collect::<Vec<_>>(), v)));
+    let last = |text: &str| reversed.first_match(text.bytes().rev()).map(|m| m.value);
+    assert_eq!(last("seventeenine"), Some(9));
+    assert_eq!(last("nineseventeen"), Some(17));
+}
@@ -0,0 +1,228 @@
+//! Repeated runs of the solvers, to get timings which are less sensitive to noise than a single
+//! measurement.
+
+use crate::json::{self, Value};
+use crate::registry::Solver;
+use crate::Error;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+pub struct Options {
+    pub warmup: usize,     // runs whose timings are discarded
+    pub iterations: usize, // measured runs
+    pub bytes: bool,       // inputs in memory split in place, instead of read through `io::Read`
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            warmup: 3,
+            iterations: 20,
+            bytes: false,
+        }
+    }
+}
+
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    fn new(samples: &mut [Duration]) -> Self {
+        samples.sort();
+        let nth = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+        Stats {
+            min: samples[0],
+            median: nth(50),
+            p95: nth(95),
+        }
+    }
+}
+
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    pub bytes: bool, // as in `Options`
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Runs the solver repeatedly on the given input, which is read from memory so that I/O does not
+/// count in the timings.
+pub fn run(s: &Solver, input: &[u8], opts: &Options) -> Result<Report, Error> {
+    let solve_once = || {
+        if opts.bytes {
+            s.solve_bytes(input)
+        } else {
+            s.solve(&mut &input[..])
+        }
+    };
+    for _ in 0..opts.warmup {
+        solve_once()?;
+    }
+
+    let iterations = opts.iterations.max(1);
+    let mut parse = Vec::with_capacity(iterations);
+    let mut solve = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let run = solve_once()?;
+        parse.push(run.parse);
+        solve.push(run.solve);
+    }
+
+    Ok(Report {
+        day: s.day,
+        part: s.part,
+        bytes: opts.bytes,
+        parse: Stats::new(&mut parse),
+        solve: Stats::new(&mut solve),
+    })
+}
+
+/// Shortest median time compared with a baseline, below which timer resolution and noise make up
+/// most of the changes.
+pub const MIN_COMPARED: Duration = Duration::from_micros(20);
+
+/// Median timings of a previous benchmark, used as reference to detect regressions. Timings of
+/// inputs read through `io::Read` and in memory are kept apart, the latter under keys such as
+/// "day01-1-bytes", since their parse and solve times are split differently.
+#[derive(Default)]
+pub struct Baseline(Vec<(u8, u8, bool, Duration, Duration)>); // day, part, bytes, parse, solve
+
+impl Baseline {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+
+        let v = json::parse(&fs::read_to_string(path)?).map_err(invalid)?;
+        let Value::Object(entries) = v else {
+            return Err(invalid(json::Error(0)));
+        };
+
+        let mut baseline = Baseline::default();
+        for (k, v) in entries {
+            let (k_part, bytes) = match k.strip_suffix("-bytes") {
+                Some(k) => (k, true),
+                None => (k.as_str(), false),
+            };
+            let key = k_part.strip_prefix("day").and_then(|k| k.split_once('-'));
+            let (Some((Ok(day), Ok(part))), Some(parse), Some(solve)) = (
+                key.map(|(d, p)| (d.parse(), p.parse())),
+                v.get("parse_ns").and_then(Value::as_f64),
+                v.get("solve_ns").and_then(Value::as_f64),
+            ) else {
+                let msg = format!("invalid baseline entry {k:?}");
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            };
+            let ns = |n: f64| Duration::from_nanos(n as u64);
+            baseline.0.push((day, part, bytes, ns(parse), ns(solve)));
+        }
+        Ok(baseline)
+    }
+
+    /// Records the median timings of the report, replacing those of the same part and mode if any.
+    pub fn record(&mut self, r: &Report) {
+        let key = (r.day, r.part, r.bytes);
+        let entry = (r.day, r.part, r.bytes, r.parse.median, r.solve.median);
+        match self.0.iter_mut().find(|b| (b.0, b.1, b.2) == key) {
+            Some(b) => *b = entry,
+            None => self.0.push(entry),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let ns = |d: &Duration| Value::Number(d.as_nanos() as f64);
+        let mut entries = self.0.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|&&(day, part, bytes, ..)| (day, part, bytes));
+        let entries = entries
+            .into_iter()
+            .map(|(day, part, bytes, parse, solve)| {
+                let timings = vec![
+                    ("parse_ns".to_string(), ns(parse)),
+                    ("solve_ns".to_string(), ns(solve)),
+                ];
+                let mode = if *bytes { "-bytes" } else { "" };
+                (format!("day{day:02}-{part}{mode}"), Value::Object(timings))
+            })
+            .collect();
+        fs::write(path, format!("{}\n", Value::Object(entries)))
+    }
+
+    /// Returns the relative change of the median parse and solve times of the report compared
+    /// with the baseline, e.g. 0.25 for a run which is 25% slower. Times which stay under
+    /// [`MIN_COMPARED`] are too noisy to be compared, and have no change.
+    pub fn change(&self, r: &Report) -> Option<(Option<f64>, Option<f64>)> {
+        let (parse, solve) = self.get(r.day, r.part, r.bytes)?;
+        let change = |new: Duration, old: Duration| {
+            let ratio = new.as_secs_f64() / old.as_secs_f64().max(f64::MIN_POSITIVE);
+            (new.max(old) >= MIN_COMPARED).then_some(ratio - 1.0)
+        };
+        Some((change(r.parse.median, parse), change(r.solve.median, solve)))
+    }
+
+    /// Median parse and solve times of the given part, with its input in memory if `bytes`.
+    pub fn get(&self, day: u8, part: u8, bytes: bool) -> Option<(Duration, Duration)> {
+        let key = (day, part, bytes);
+        let (.., parse, solve) = self.0.iter().find(|b| (b.0, b.1, b.2) == key)?;
+        Some((*parse, *solve))
+    }
+}
+
+#[test]
+fn percentiles() {
+    let mut samples = (1..=20)
+        .rev()
+        .map(Duration::from_millis)
+        .collect::<Vec<_>>();
+    let stats = Stats::new(&mut samples);
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.median, Duration::from_millis(10));
+    assert_eq!(stats.p95, Duration::from_millis(19));
+}
+
+#[test]
+fn save_measured_parts() {
+    let stats = |us| {
+        let d = Duration::from_micros(us);
+        Stats {
+            min: d,
+            median: d,
+            p95: d,
+        }
+    };
+    let report = |day, parse, solve| Report {
+        day,
+        part: 1,
+        bytes: false,
+        parse: stats(parse),
+        solve: stats(solve),
+    };
+    let path = std::env::temp_dir().join(format!("aoc-baseline-{}.json", std::process::id()));
+    let mut baseline = Baseline::default();
+    baseline.record(&report(1, 100, 100));
+    baseline.record(&report(2, 100, 100));
+    baseline.save(&path).unwrap();
+
+    // Saving day 2 again keeps day 1, whose timings in memory are kept apart.
+    let mut baseline = Baseline::load(&path).unwrap();
+    baseline.record(&report(2, 1, 300));
+    baseline.record(&Report {
+        bytes: true,
+        ..report(1, 0, 50)
+    });
+    baseline.save(&path).unwrap();
+    let baseline = Baseline::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let us = Duration::from_micros;
+    assert_eq!(baseline.get(1, 1, false), Some((us(100), us(100))));
+    assert_eq!(baseline.get(1, 1, true), Some((us(0), us(50))));
+
+    // A parse time of a few microseconds which doubles is not compared.
+    assert_eq!(
+        baseline.change(&report(2, 2, 150)),
+        Some((None, Some(-0.5)))
+    );
+}
@@ -0,0 +1,605 @@
+//! Differential testing of the optimized solvers against straightforward reference
+//! implementations, on randomly generated inputs.
+//!
+//! The first input on which a solver and its reference disagree is minimised before being
+//! reported, along with the seed which generates it again.
+
+use crate::day01::Day01;
+use crate::day04::Day04;
+use crate::day05::Day05;
+use crate::day06::Day06;
+use crate::day08::Day08;
+use crate::gen::MAP_NAMES;
+use crate::rng::Rng;
+use crate::{pool, Error, Solution};
+use std::collections::HashSet;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A puzzle part whose optimized solver must agree with a reference implementation.
+pub trait Reference {
+    const NAME: &'static str;
+
+    type Input: Clone;
+
+    fn generate(rng: &mut Rng) -> Self::Input;
+
+    /// Inputs simpler than the given one, tried in order when minimising a failing case.
+    fn shrink(input: &Self::Input) -> Vec<Self::Input>;
+
+    /// Puzzle text of the input.
+    fn render(input: &Self::Input) -> String;
+
+    /// Runs the solver under test on the puzzle text.
+    fn optimized(text: &str) -> Result<u64, Error>;
+
+    fn reference(input: &Self::Input) -> Result<u64, Error>;
+}
+
+/// Input on which a solver and its reference disagree.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub name: &'static str,
+    pub seed: u64, // generates the case before its minimisation
+    pub input: String,
+    pub optimized: Result<u64, String>,
+    pub reference: Result<u64, String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let show = |r: &Result<u64, String>| match r {
+            Ok(v) => v.to_string(),
+            Err(e) => format!("error: {e}"),
+        };
+        write!(
+            f,
+            "{} differs from its reference on the case of seed {}, minimised to:\n{}\
+             optimized: {}\nreference: {}",
+            self.name,
+            self.seed,
+            self.input,
+            show(&self.optimized),
+            show(&self.reference)
+        )
+    }
+}
+
+/// Compares `R` with its reference on `cases` inputs, and returns the first disagreement.
+pub fn compare<R: Reference>(seed: u64, cases: usize) -> Result<(), Mismatch> {
+    let mut seeds = Rng::new(seed);
+    for _ in 0..cases {
+        let seed = seeds.next_u64();
+        let mut input = R::generate(&mut Rng::new(seed));
+        let Some(mut outcomes) = differ::<R>(&input) else {
+            continue;
+        };
+        'shrink: loop {
+            for smaller in R::shrink(&input) {
+                if let Some(o) = differ::<R>(&smaller) {
+                    (input, outcomes) = (smaller, o);
+                    continue 'shrink;
+                }
+            }
+            break;
+        }
+        return Err(Mismatch {
+            name: R::NAME,
+            seed,
+            input: R::render(&input),
+            optimized: outcomes.0,
+            reference: outcomes.1,
+        });
+    }
+    Ok(())
+}
+
+/// Compares every solver having a reference, and returns their disagreements.
+pub fn compare_all(seed: u64, cases: usize) -> Vec<Mismatch> {
+    [
+        compare::<Day01Part2>(seed, cases),
+        compare::<Day04Part2>(seed, cases),
+        compare::<Day05Part2>(seed, cases),
+        compare::<Day06Part1>(seed, cases),
+        compare::<Day06Part2>(seed, cases),
+        compare::<Day08Part2>(seed, cases),
+    ]
+    .into_iter()
+    .filter_map(Result::err)
+    .collect()
+}
+
+type Outcome = Result<u64, String>;
+
+fn differ<R: Reference>(input: &R::Input) -> Option<(Outcome, Outcome)> {
+    let text = R::render(input);
+    let optimized = guard(|| R::optimized(&text));
+    let reference = guard(|| R::reference(input));
+    (optimized != reference).then_some((optimized, reference))
+}
+
+// Reports a panic as an error, so that it is minimised like any other disagreement.
+fn guard(f: impl FnOnce() -> Result<u64, Error>) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(r) => r.map_err(|e| e.to_string()),
+        Err(payload) => Err(format!("panicked: {}", pool::message(payload))),
+    }
+}
+
+// Copies of `items` without one of them, for each of them.
+fn without_each<T: Clone>(items: &[T]) -> impl Iterator<Item = Vec<T>> + '_ {
+    (0..items.len()).map(|i| [&items[..i], &items[i + 1..]].concat())
+}
+
+const DIGIT_WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+pub struct Day01Part2;
+
+impl Reference for Day01Part2 {
+    const NAME: &'static str = "day01-2";
+
+    type Input = Vec<String>;
+
+    // Lines of digits, digit words and letters, which often overlap like in "eightwo".
+    fn generate(rng: &mut Rng) -> Self::Input {
+        let tokens = DIGIT_WORDS
+            .iter()
+            .copied()
+            .chain(["1", "5", "9", "e", "n", "o", "t", "x"])
+            .collect::<Vec<_>>();
+        (0..rng.range(1, 8))
+            .map(|_| (0..rng.range(0, 8)).map(|_| *rng.pick(&tokens)).collect())
+            .collect()
+    }
+
+    fn shrink(lines: &Self::Input) -> Vec<Self::Input> {
+        let mut smaller = without_each(lines).collect::<Vec<_>>();
+        for (i, line) in lines.iter().enumerate() {
+            for j in 0..line.len() {
+                let mut lines = lines.clone();
+                lines[i].remove(j);
+                smaller.push(lines);
+            }
+        }
+        smaller
+    }
+
+    fn render(lines: &Self::Input) -> String {
+        lines.iter().map(|l| format!("{l}\n")).collect()
+    }
+
+    fn optimized(text: &str) -> Result<u64, Error> {
+        Day01::part2(&Day01::parse(text.as_bytes())?)
+    }
+
+    // Looks for a digit or a digit word at every position.
+    fn reference(lines: &Self::Input) -> Result<u64, Error> {
+        Ok(lines
+            .iter()
+            .map(|line| {
+                let digits = (0..line.len())
+                    .filter_map(|i| {
+                        let rest = &line[i..];
+                        let c = rest.as_bytes()[0];
+                        if c.is_ascii_digit() {
+                            return Some(u64::from(c - b'0'));
+                        }
+                        let word = DIGIT_WORDS.iter().position(|w| rest.starts_with(w))?;
+                        Some(word as u64 + 1)
+                    })
+                    .collect::<Vec<_>>();
+                match (digits.first(), digits.last()) {
+                    (Some(first), Some(last)) => first * 10 + last,
+                    _ => 0,
+                }
+            })
+            .sum())
+    }
+}
+
+pub struct Day04Part2;
+
+impl Reference for Day04Part2 {
+    const NAME: &'static str = "day04-2";
+
+    type Input = Vec<(Vec<u32>, Vec<u32>)>; // winning numbers and numbers we have, of each card
+
+    fn generate(rng: &mut Rng) -> Self::Input {
+        let numbers = |rng: &mut Rng, n| (0..n).map(|_| rng.range(1, 30) as u32).collect();
+        (0..rng.range(1, 12))
+            .map(|_| (numbers(rng, 5), numbers(rng, 8)))
+            .collect()
+    }
+
+    fn shrink(cards: &Self::Input) -> Vec<Self::Input> {
+        let mut smaller = without_each(cards).collect::<Vec<_>>();
+        for (i, (winning, have)) in cards.iter().enumerate() {
+            for winning in without_each(winning) {
+                let mut cards = cards.clone();
+                cards[i].0 = winning;
+                smaller.push(cards);
+            }
+            for have in without_each(have) {
+                let mut cards = cards.clone();
+                cards[i].1 = have;
+                smaller.push(cards);
+            }
+        }
+        smaller
+    }
+
+    fn render(cards: &Self::Input) -> String {
+        let numbers = |ns: &[u32]| {
+            ns.iter()
+                .map(|n| format!("{n:>2}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        cards
+            .iter()
+            .enumerate()
+            .map(|(i, (winning, have))| {
+                format!(
+                    "Card {:>3}: {} | {}\n",
+                    i + 1,
+                    numbers(winning),
+                    numbers(have)
+                )
+            })
+            .collect()
+    }
+
+    fn optimized(text: &str) -> Result<u64, Error> {
+        Day04::part2(&Day04::parse(text.as_bytes())?)
+    }
+
+    // Counts the copies of every card, in a table indexed by card.
+    fn reference(cards: &Self::Input) -> Result<u64, Error> {
+        let mut copies = vec![1; cards.len()];
+        for (i, (winning, have)) in cards.iter().enumerate() {
+            let matches = have.iter().filter(|n| winning.contains(n)).count();
+            for j in i + 1..=(i + matches).min(cards.len() - 1) {
+                copies[j] += copies[i];
+            }
+        }
+        Ok(copies.iter().sum())
+    }
+}
+
+pub struct Day05Part2;
+
+#[derive(Clone)]
+pub struct Almanac {
+    seeds: Vec<(u64, u64)>,          // start and length of each range
+    maps: [Vec<(u64, u64, u64)>; 7], // destination start, source start and length of each range
+}
+
+impl Reference for Day05Part2 {
+    const NAME: &'static str = "day05-2";
+
+    type Input = Almanac;
+
+    fn generate(rng: &mut Rng) -> Self::Input {
+        let seeds = (0..rng.range(1, 4))
+            .map(|_| (rng.range(0, 100), rng.range(1, 20)))
+            .collect();
+        let maps = [(); 7].map(|_| {
+            // Source ranges do not overlap, as in the puzzle input.
+            let mut src = rng.range(0, 20);
+            (0..rng.range(0, 4))
+                .map(|_| {
+                    let range = (rng.range(0, 100), src, rng.range(1, 20));
+                    src += range.2 + rng.range(0, 20);
+                    range
+                })
+                .collect()
+        });
+        Almanac { seeds, maps }
+    }
+
+    fn shrink(a: &Self::Input) -> Vec<Self::Input> {
+        let mut smaller = Vec::new();
+        if a.seeds.len() > 1 {
+            for seeds in without_each(&a.seeds) {
+                smaller.push(Almanac { seeds, ..a.clone() });
+            }
+        }
+        for (m, map) in a.maps.iter().enumerate() {
+            for map in without_each(map) {
+                let mut a = a.clone();
+                a.maps[m] = map;
+                smaller.push(a);
+            }
+        }
+        for (i, &(start, len)) in a.seeds.iter().enumerate() {
+            if len > 1 {
+                let mut a = a.clone();
+                a.seeds[i] = (start, len / 2);
+                smaller.push(a);
+            }
+        }
+        smaller
+    }
+
+    fn render(a: &Self::Input) -> String {
+        let seeds = a.seeds.iter().map(|(start, len)| format!(" {start} {len}"));
+        render_almanac(seeds.collect(), &a.maps)
+    }
+
+    fn optimized(text: &str) -> Result<u64, Error> {
+        Day05::part2(&Day05::parse(text.as_bytes())?)
+    }
+
+    // Maps every seed of the ranges one by one, with the solver of part 1.
+    fn reference(a: &Self::Input) -> Result<u64, Error> {
+        let seeds = a
+            .seeds
+            .iter()
+            .flat_map(|&(start, len)| start..start + len)
+            .map(|s| format!(" {s}"));
+        let text = render_almanac(seeds.collect(), &a.maps);
+        Day05::part1(&Day05::parse(text.as_bytes())?)
+    }
+}
+
+fn render_almanac(seeds: String, maps: &[Vec<(u64, u64, u64)>; 7]) -> String {
+    let maps = MAP_NAMES.iter().zip(maps).map(|(name, ranges)| {
+        let ranges = ranges
+            .iter()
+            .map(|(dst, src, len)| format!("{dst} {src} {len}\n"));
+        format!("{name} map:\n{}", ranges.collect::<String>())
+    });
+    format!("seeds:{seeds}\n\n{}", maps.collect::<Vec<_>>().join("\n"))
+}
+
+// Distance travelled when pressing the button for `press` out of `time` milliseconds.
+fn distance(press: u64, time: u64) -> u64 {
+    press * (time - press)
+}
+
+// Counts the winning presses one by one.
+fn ways(time: u64, record: u64) -> u64 {
+    (0..=time).filter(|&p| distance(p, time) > record).count() as u64
+}
+
+pub struct Day06Part1;
+
+impl Reference for Day06Part1 {
+    const NAME: &'static str = "day06-1";
+
+    type Input = Vec<(u64, u64)>; // time and record distance of each race
+
+    fn generate(rng: &mut Rng) -> Self::Input {
+        (0..rng.range(1, 5))
+            .map(|_| {
+                let time = rng.range(1, 40);
+                (time, rng.range(0, distance(time / 2, time) + 5))
+            })
+            .collect()
+    }
+
+    fn shrink(races: &Self::Input) -> Vec<Self::Input> {
+        let mut smaller = Vec::new();
+        if races.len() > 1 {
+            smaller.extend(without_each(races));
+        }
+        for (i, &(time, record)) in races.iter().enumerate() {
+            for race in [(time / 2, record), (time - 1, record), (time, record / 2)] {
+                if race != (time, record) && race.0 > 0 {
+                    let mut races = races.clone();
+                    races[i] = race;
+                    smaller.push(races);
+                }
+            }
+        }
+        smaller
+    }
+
+    fn render(races: &Self::Input) -> String {
+        let (times, records): (Vec<_>, Vec<_>) = races
+            .iter()
+            .map(|(t, r)| (format!(" {t:>3}"), format!(" {r:>3}")))
+            .unzip();
+        format!(
+            "Time:    {}\nDistance:{}\n",
+            times.concat(),
+            records.concat()
+        )
+    }
+
+    fn optimized(text: &str) -> Result<u64, Error> {
+        Day06::part1(&Day06::parse(text.as_bytes())?).map(u64::from)
+    }
+
+    fn reference(races: &Self::Input) -> Result<u64, Error> {
+        Ok(races
+            .iter()
+            .map(|&(time, record)| ways(time, record))
+            .product())
+    }
+}
+
+pub struct Day06Part2;
+
+impl Reference for Day06Part2 {
+    const NAME: &'static str = "day06-2";
+
+    type Input = (u64, u64); // time and record distance of the single race
+
+    // A race which can be won, as in the puzzle input.
+    fn generate(rng: &mut Rng) -> Self::Input {
+        let time = rng.range(2, 5000);
+        (time, rng.range(0, distance(time / 2, time)))
+    }
+
+    fn shrink(&(time, record): &Self::Input) -> Vec<Self::Input> {
+        [(time / 2, record), (time - 1, record), (time, record / 2)]
+            .into_iter()
+            .filter(|&(t, r)| (t, r) != (time, record) && t >= 2 && r < distance(t / 2, t))
+            .collect()
+    }
+
+    fn render(&(time, record): &Self::Input) -> String {
+        format!("Time:      {time}\nDistance:  {record}\n")
+    }
+
+    fn optimized(text: &str) -> Result<u64, Error> {
+        Day06::part2(&Day06::parse(text.as_bytes())?)
+    }
+
+    fn reference(&(time, record): &Self::Input) -> Result<u64, Error> {
+        Ok(ways(time, record))
+    }
+}
+
+pub struct Day08Part2;
+
+#[derive(Clone)]
+pub struct Maps {
+    instructions: String,
+    nodes: Vec<(usize, usize)>, // left and right node of each node, named by NODE_NAMES
+}
+
+const NODE_NAMES: [&str; 8] = ["AAA", "BBA", "CCZ", "DDZ", "EEE", "FFF", "GGA", "HHZ"];
+
+// The reference gives up after this many steps.
+const MAX_STEPS: u64 = 10_000;
+
+impl Reference for Day08Part2 {
+    const NAME: &'static str = "day08-2";
+
+    type Input = Maps;
+
+    fn generate(rng: &mut Rng) -> Self::Input {
+        let instructions = (0..rng.range(1, 5))
+            .map(|_| *rng.pick(&['L', 'R']))
+            .collect();
+        let n = rng.range(1, NODE_NAMES.len() as u64 + 1);
+        let nodes = (0..n)
+            .map(|_| (rng.range(0, n) as usize, rng.range(0, n) as usize))
+            .collect();
+        Maps {
+            instructions,
+            nodes,
+        }
+    }
+
+    fn shrink(m: &Self::Input) -> Vec<Self::Input> {
+        let mut smaller = Vec::new();
+        if m.instructions.len() > 1 {
+            for i in 0..m.instructions.len() {
+                let mut m = m.clone();
+                m.instructions.remove(i);
+                smaller.push(m);
+            }
+        }
+        // Without the last node, whose references now point to the first one.
+        if let Some((_, rest)) = m.nodes.split_last() {
+            let last = rest.len();
+            let first = |n: usize| if n == last { 0 } else { n };
+            smaller.push(Maps {
+                nodes: rest.iter().map(|&(l, r)| (first(l), first(r))).collect(),
+                ..m.clone()
+            });
+        }
+        smaller
+    }
+
+    fn render(m: &Self::Input) -> String {
+        let nodes = m.nodes.iter().enumerate().map(|(i, &(l, r))| {
+            let [n, l, r] = [i, l, r].map(|n| NODE_NAMES[n]);
+            format!("{n} = ({l}, {r})\n")
+        });
+        format!("{}\n\n{}", m.instructions, nodes.collect::<String>())
+    }
+
+    fn optimized(text: &str) -> Result<u64, Error> {
+        match Day08::parse(text.as_bytes()).and_then(|map| Day08::part2(&map)) {
+            Ok(t) if t > MAX_STEPS => Err(Error::NoAnswer("too many steps")),
+            r => r,
+        }
+    }
+
+    // Moves all the ghosts at once, step by step, until they are all at an end or back in a state
+    // they were already in.
+    fn reference(m: &Self::Input) -> Result<u64, Error> {
+        let mut ghosts = (0..m.nodes.len())
+            .filter(|&n| NODE_NAMES[n].ends_with('A'))
+            .collect::<Vec<_>>();
+        if ghosts.is_empty() {
+            return Err(Error::NoAnswer("no node ends with 'A'"));
+        }
+        let instructions = m.instructions.as_bytes();
+        let mut seen = HashSet::new();
+        for t in 0..=MAX_STEPS {
+            if t > 0 && ghosts.iter().all(|&g| NODE_NAMES[g].ends_with('Z')) {
+                return Ok(t);
+            }
+            if !seen.insert((ghosts.clone(), t as usize % instructions.len())) {
+                return Err(Error::NoAnswer(
+                    "the ghosts are never all on nodes ending with 'Z'",
+                ));
+            }
+            for g in &mut ghosts {
+                let (l, r) = m.nodes[*g];
+                *g = if instructions[t as usize % instructions.len()] == b'L' {
+                    l
+                } else {
+                    r
+                };
+            }
+        }
+        Err(Error::NoAnswer("too many steps"))
+    }
+}
+
+#[test]
+fn optimized_match_references() {
+    // Another seed explores other cases: AOC_SEED=<n> cargo test optimized_match_references
+    let seed = std::env::var("AOC_SEED").map_or(2023, |s| s.parse().expect("AOC_SEED"));
+    let mismatches = compare_all(seed, 300);
+    assert!(
+        mismatches.is_empty(),
+        "{}",
+        mismatches
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    );
+}
+
+#[test]
+fn minimise_mismatch() {
+    // Sums numbers, but overflows on large ones.
+    struct Faulty;
+    impl Reference for Faulty {
+        const NAME: &'static str = "faulty";
+        type Input = Vec<u64>;
+        fn generate(rng: &mut Rng) -> Self::Input {
+            (0..10).map(|_| rng.range(0, 300)).collect()
+        }
+        fn shrink(ns: &Self::Input) -> Vec<Self::Input> {
+            without_each(ns).collect()
+        }
+        fn render(ns: &Self::Input) -> String {
+            ns.iter().map(|n| format!("{n}\n")).collect()
+        }
+        fn optimized(text: &str) -> Result<u64, Error> {
+            Ok(text
+                .lines()
+                .map(|l| u64::from(l.parse::<u8>().unwrap()))
+                .sum())
+        }
+        fn reference(ns: &Self::Input) -> Result<u64, Error> {
+            Ok(ns.iter().sum())
+        }
+    }
+
+    let m = compare::<Faulty>(1, 10).unwrap_err();
+    assert_eq!(m.input.lines().count(), 1);
+    assert!(m.input.trim().parse::<u64>().unwrap() > 255);
+    assert!(m.optimized.unwrap_err().starts_with("panicked"));
+}
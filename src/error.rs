@@ -0,0 +1,131 @@
+use crate::input;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    Input(input::Error),
+    Io(io::Error),
+    Parse(ParseError),
+    NoAnswer(&'static str), // for a well-formed input, with the reason
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Input(e) => write!(f, "{e}"),
+            Error::Io(e) => write!(f, "cannot read input: {e}"),
+            Error::Parse(e) => write!(f, "{e}"),
+            Error::NoAnswer(reason) => write!(f, "no answer: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Input(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Parse(e) => Some(e),
+            Error::NoAnswer(_) => None,
+        }
+    }
+}
+
+impl From<input::Error> for Error {
+    fn from(e: input::Error) -> Self {
+        Error::Input(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+/// Location and cause of a malformed puzzle input.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,   // 1-based
+    pub column: usize, // 1-based, in bytes
+    pub found: String, // empty at the end of the line
+    pub expected: &'static str,
+}
+
+impl ParseError {
+    /// Creates an error located at the given 0-based line index and byte offset in that line.
+    pub fn new(
+        day: u8,
+        line_idx: usize,
+        offset: usize,
+        found: impl Into<String>,
+        expected: &'static str,
+    ) -> Self {
+        ParseError {
+            day,
+            line: line_idx + 1,
+            column: offset + 1,
+            found: found.into(),
+            expected,
+        }
+    }
+
+    /// Creates an error for the invalid `token` which ends right before the byte `end` of `line`.
+    /// An empty token is reported as the character found at `end`.
+    pub fn token(
+        day: u8,
+        line_idx: usize,
+        line: &[u8],
+        end: usize,
+        token: &[u8],
+        expected: &'static str,
+    ) -> Self {
+        if token.is_empty() {
+            Self::at(day, line_idx, line, end, expected)
+        } else {
+            let found = String::from_utf8_lossy(token);
+            Self::new(day, line_idx, end - token.len(), found, expected)
+        }
+    }
+
+    /// Creates an error for the character starting at byte `offset` of `line`, or for the end of
+    /// `line`.
+    pub fn at(
+        day: u8,
+        line_idx: usize,
+        line: &[u8],
+        offset: usize,
+        expected: &'static str,
+    ) -> Self {
+        // A character is at most 4 bytes long.
+        let rest = line.get(offset..).unwrap_or_default();
+        let found = String::from_utf8_lossy(&rest[..rest.len().min(4)]);
+        let found = found.chars().take(1).collect::<String>();
+        Self::new(day, line_idx, offset, found, expected)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day{:02} input, line {}, column {}: expected {}, found ",
+            self.day, self.line, self.column, self.expected
+        )?;
+        if self.found.is_empty() {
+            write!(f, "end of line")
+        } else {
+            write!(f, "{:?}", self.found)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,80 @@
+//! Sample inputs of the puzzles, stored as `dayNN/examples/*.txt` files whose expected answers are
+//! recorded next to them in `dayNN/examples/answers.toml`, in the format of [`crate::answers`].
+
+use crate::answers::{self, Answer};
+use crate::input::Source;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DIR: &str = "examples";
+
+/// Directory holding the examples of the given day in this repository.
+pub fn dir(day: u8) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(format!("day{day:02}"))
+        .join(DIR)
+}
+
+/// Loads the expected answers to the examples of the given day. Every example file must have at
+/// least one, so that a new one cannot be forgotten.
+pub fn load(day: u8) -> io::Result<Vec<Answer>> {
+    let dir = dir(day);
+    let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    let answers = match answers::load(&dir.join(answers::FILE)) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        r => r?,
+    };
+    if let Some(a) = answers.iter().find(|a| a.day != day) {
+        return Err(invalid(format!(
+            "answer to day{:02} in {}",
+            a.day,
+            dir.display()
+        )));
+    }
+
+    let entries = match fs::read_dir(&dir) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(answers),
+        r => r?,
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt")
+            && !answers
+                .iter()
+                .any(|a| matches!(&a.input, Source::File(p) if *p == path))
+        {
+            return Err(invalid(format!("no expected answer to {}", path.display())));
+        }
+    }
+    Ok(answers)
+}
+
+#[test]
+fn examples() {
+    use crate::answers::Outcome;
+    use crate::registry::{self, SOLVERS};
+
+    let mut days = SOLVERS.iter().map(|s| s.day).collect::<Vec<_>>();
+    days.dedup();
+
+    let mut failures = Vec::new();
+    for day in days {
+        for a in load(day).unwrap_or_else(|e| panic!("{e}")) {
+            let Some(s) = registry::find(a.day, a.part) else {
+                failures.push(format!("{}: no solver for part {}", a.input, a.part));
+                continue;
+            };
+            match a.check(s) {
+                Outcome::Pass => {}
+                Outcome::Fail { found } => failures.push(format!(
+                    "{} part {}: expected {}, found {found}",
+                    a.input, a.part, a.expected
+                )),
+                Outcome::Error(e) => failures.push(format!("{} part {}: {e}", a.input, a.part)),
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,242 @@
+//! Download of the puzzle inputs, which are personal and cannot be committed.
+//!
+//! The session token is read from a configuration file:
+//!
+//! ```toml
+//! session = "53616c7465645f5f..."           # value of the adventofcode.com session cookie
+//! base_url = "https://adventofcode.com"     # optional, e.g. a local mock server
+//! cache_dir = "/home/me/aoc-inputs"         # optional, defaults to the input directory
+//! backend = "curl"                          # optional, "curl" or "http"
+//! ```
+
+use crate::{input, toml};
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Environment variable pointing at the configuration file, to override its default location.
+pub const CONFIG_VAR: &str = "AOC_CONFIG";
+
+const YEAR: u16 = 2023;
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "adventofcode2023 aoc fetch";
+
+pub struct Config {
+    pub session: String,
+    pub base_url: String,
+    pub cache_dir: PathBuf,
+    pub backend: Option<String>,
+}
+
+impl Config {
+    /// Default location of the configuration: `$AOC_CONFIG`, or `aoc/config.toml` in the user's
+    /// configuration directory.
+    pub fn path() -> PathBuf {
+        if let Some(path) = env::var_os(CONFIG_VAR) {
+            return PathBuf::from(path);
+        }
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|h| Path::new(&h).join(".config")))
+            .unwrap_or_default()
+            .join("aoc")
+            .join("config.toml")
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+        let tables = toml::parse(&fs::read_to_string(path)?).map_err(|e| invalid(e.to_string()))?;
+        if let Some(t) = tables.get(1) {
+            return Err(invalid(format!("unexpected table [{}]", t.name)));
+        }
+        let root = &tables[0];
+        let string = |key| match root.get(key) {
+            None => Ok(None),
+            Some(toml::Value::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(invalid(format!("{key} must be a string"))),
+        };
+
+        let Some(session) = string("session")? else {
+            return Err(invalid(format!("no session in {}", path.display())));
+        };
+        Ok(Config {
+            session,
+            base_url: string("base_url")?.unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+            cache_dir: string("cache_dir")?.map_or_else(input::dir, PathBuf::from),
+            backend: string("backend")?,
+        })
+    }
+
+    /// Backend named in the configuration, or the one able to handle the base URL.
+    pub fn backend(&self) -> io::Result<Box<dyn Backend>> {
+        match self.backend.as_deref() {
+            Some("curl") => Ok(Box::new(Curl)),
+            Some("http") => Ok(Box::new(Http)),
+            None if self.base_url.starts_with("http://") => Ok(Box::new(Http)),
+            None => Ok(Box::new(Curl)),
+            Some(b) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown backend {b:?}, expected curl or http"),
+            )),
+        }
+    }
+}
+
+/// HTTP client performing GET requests.
+pub trait Backend {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> io::Result<Vec<u8>>;
+}
+
+/// Backend running the `curl` command, which supports HTTPS.
+pub struct Curl;
+
+impl Backend for Curl {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> io::Result<Vec<u8>> {
+        // Headers are passed on stdin so that the session token does not show in the process list.
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--header", "@-", url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        for (k, v) in headers {
+            writeln!(stdin, "{k}: {v}")?;
+        }
+        drop(stdin);
+
+        let out = child.wait_with_output()?;
+        if !out.status.success() {
+            let msg = String::from_utf8_lossy(&out.stderr);
+            return Err(io::Error::other(format!("curl {url}: {}", msg.trim())));
+        }
+        Ok(out.stdout)
+    }
+}
+
+/// Backend speaking plain HTTP/1.0 over TCP, meant for local mock servers.
+pub struct Http;
+
+impl Backend for Http {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> io::Result<Vec<u8>> {
+        let unsupported =
+            || io::Error::new(io::ErrorKind::InvalidInput, format!("{url}: not http"));
+        let rest = url.strip_prefix("http://").ok_or_else(unsupported)?;
+        let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let addr = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{host}:80")
+        };
+
+        let mut stream = TcpStream::connect(addr)?;
+        let path = if path.is_empty() { "/" } else { path };
+        write!(stream, "GET {path} HTTP/1.0\r\nHost: {host}\r\n")?;
+        for (k, v) in headers {
+            write!(stream, "{k}: {v}\r\n")?;
+        }
+        write!(stream, "\r\n")?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        let malformed = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{url}: malformed response"),
+            )
+        };
+        let split = response
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .ok_or_else(malformed)?;
+        let status = response[..split]
+            .split(|&b| b == b' ')
+            .nth(1)
+            .and_then(|s| std::str::from_utf8(s).ok())
+            .ok_or_else(malformed)?;
+        if status != "200" {
+            return Err(io::Error::other(format!("{url}: HTTP status {status}")));
+        }
+        Ok(response.split_off(split + 4))
+    }
+}
+
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Downloads the input of the given day into `<cache dir>/dayNN/input`, unless it is already
+/// there.
+pub fn fetch(config: &Config, backend: &dyn Backend, day: u8) -> io::Result<Fetched> {
+    let dir = config.cache_dir.join(format!("day{day:02}"));
+    let path = dir.join("input");
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+
+    let url = format!(
+        "{}/{YEAR}/day/{day}/input",
+        config.base_url.trim_end_matches('/')
+    );
+    let cookie = format!("session={}", config.session);
+    let input = backend.get(&url, &[("Cookie", &cookie), ("User-Agent", USER_AGENT)])?;
+
+    // Written aside first, so that an interrupted download is not taken for a cached input.
+    fs::create_dir_all(&dir)?;
+    let tmp = dir.join("input.part");
+    fs::write(&tmp, input)?;
+    fs::rename(&tmp, &path)?;
+    Ok(Fetched::Downloaded(path))
+}
+
+#[test]
+fn fetch_once_from_mock_server() {
+    use std::io::BufRead;
+    use std::net::TcpListener;
+    use std::thread;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    // Serves a single request, so that a second download would fail.
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        for l in io::BufReader::new(&stream).lines() {
+            let l = l.unwrap();
+            if l.is_empty() {
+                break;
+            }
+            request.push(l);
+        }
+        (&stream)
+            .write_all(b"HTTP/1.0 200 OK\r\n\r\nTime: 7\nDistance: 9\n")
+            .unwrap();
+        request
+    });
+
+    let cache_dir = env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+    let config = Config {
+        session: "s3cr3t".to_string(),
+        base_url: format!("http://{addr}/"),
+        cache_dir: cache_dir.clone(),
+        backend: None,
+    };
+    let backend = config.backend().unwrap();
+
+    let first = fetch(&config, &*backend, 6).unwrap();
+    let request = server.join().unwrap();
+    let second = fetch(&config, &*backend, 6).unwrap();
+    let input = fs::read_to_string(cache_dir.join("day06").join("input")).unwrap();
+    fs::remove_dir_all(&cache_dir).unwrap();
+
+    assert!(matches!(first, Fetched::Downloaded(_)));
+    assert!(matches!(second, Fetched::Cached(_)));
+    assert_eq!(request[0], "GET /2023/day/6/input HTTP/1.0");
+    assert!(request.contains(&"Cookie: session=s3cr3t".to_string()));
+    assert_eq!(input, "Time: 7\nDistance: 9\n");
+}
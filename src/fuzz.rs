@@ -0,0 +1,84 @@
+//! Robustness of the solvers to malformed input: whatever the bytes, they must return an answer or
+//! an error, without panicking nor looping forever.
+//!
+//! The fuzz targets in `fuzz/` call [`solve`], for example starting from the examples with:
+//!
+//! ```sh
+//! cargo +nightly fuzz run day08 fuzz/corpus/day08 day08/examples
+//! ```
+
+use crate::registry::SOLVERS;
+
+/// Runs both parts of the given day on arbitrary bytes, ignoring their answers and errors.
+///
+/// The solvers of inputs held in memory and the streaming ones run too, and must find the same
+/// answers or errors as the ones reading the input.
+pub fn solve(day: u8, data: &[u8]) {
+    for s in SOLVERS.iter().filter(|s| s.day == day) {
+        let show = |r: Result<crate::registry::Run, crate::Error>| match r {
+            Ok(run) => run.answer,
+            Err(e) => e.to_string(),
+        };
+        let read = show(s.solve(&mut &data[..]));
+        let bytes = show(s.solve_bytes(data));
+        let stream = show(s.stream(&mut &data[..]));
+        assert_eq!(read, bytes, "day{day:02} part {}", s.part);
+        assert_eq!(read, stream, "day{day:02} part {}, streamed", s.part);
+    }
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #![proptest_config(proptest::test_runner::Config::with_cases(5000))]
+
+    // Mutations of the examples, which reach deeper into the solvers than random bytes.
+    #[test]
+    fn malformed_examples(
+        day in proptest::sample::select(SOLVERS.iter().map(|s| s.day).collect::<Vec<_>>()),
+        example in proptest::num::usize::ANY,
+        edits in proptest::collection::vec(
+            (
+                proptest::num::usize::ANY,
+                0..3u8,
+                proptest::sample::select(&[
+                    "0", "1", "9", "99999999999999999999", " ", "\n", ":", ",", "|", "=", "(",
+                    "A", "L", "R", "Z", "J", "*", "-", "é",
+                ][..]),
+            ),
+            0..12,
+        ),
+    ) {
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let mut examples = std::fs::read_dir(crate::examples::dir(day))
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "txt"))
+            .collect::<Vec<_>>();
+        examples.sort();
+        proptest::prop_assume!(!examples.is_empty());
+        let mut input = std::fs::read(&examples[example % examples.len()]).unwrap();
+        for (at, op, s) in edits {
+            let at = at % (input.len() + 1);
+            match op {
+                0 => drop(input.splice(at..at, s.bytes())), // insertion
+                1 => drop(input.splice(at..(at + s.len()).min(input.len()), s.bytes())), // replacement
+                _ => drop(input.drain(at..(at + s.len()).min(input.len()))), // deletion
+            }
+        }
+
+        let (done, finished) = mpsc::channel();
+        let data = input.clone();
+        std::thread::spawn(move || {
+            let r = std::panic::catch_unwind(|| solve(day, &data));
+            let _ = done.send(r.is_ok());
+        });
+        let input = String::from_utf8_lossy(&input);
+        match finished.recv_timeout(Duration::from_secs(5)) {
+            Ok(true) => (),
+            Ok(false) => proptest::prop_assert!(false, "day{day:02} panicked on:\n{input}"),
+            Err(_) => proptest::prop_assert!(false, "day{day:02} looped on:\n{input}"),
+        }
+    }
+}
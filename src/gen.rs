@@ -0,0 +1,570 @@
+//! Synthetic puzzle inputs of any size, since the real inputs cannot be shared.
+//!
+//! Each input is built from parts whose contribution to the answers is known, so the expected
+//! answers come without running the solvers. An answer is unknown when it overflows the type
+//! returned by the solver, which then reports that there is no answer.
+
+use crate::rng::Rng;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Generated input, with the expected answer of each part.
+pub struct Generated {
+    pub input: String,
+    pub answers: [Option<String>; 2],
+}
+
+/// Generates an input for `day` of about `size` lines, or `None` if the day has no generator.
+///
+/// The same seed always generates the same input.
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<Generated> {
+    let rng = &mut Rng::new(seed);
+    Some(match day {
+        1 => calibration(rng, size),
+        2 => games(rng, size),
+        3 => schematic(rng, size),
+        4 => scratchcards(rng, size),
+        5 => almanac(rng, size),
+        6 => races(rng, size),
+        7 => hands(rng, size),
+        8 => network(rng, size),
+        _ => return None,
+    })
+}
+
+// Answer as the solver returns it, if it fits in its type `T`.
+fn answer<T: TryFrom<u128> + ToString>(n: u128) -> Option<String> {
+    T::try_from(n).ok().map(|n| n.to_string())
+}
+
+const DIGIT_WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+// Spelled digits sharing letters, which must count as both digits.
+const OVERLAPS: [(&str, [u128; 2]); 8] = [
+    ("oneight", [1, 8]),
+    ("twone", [2, 1]),
+    ("threeight", [3, 8]),
+    ("fiveight", [5, 8]),
+    ("sevenine", [7, 9]),
+    ("eightwo", [8, 2]),
+    ("eighthree", [8, 3]),
+    ("nineight", [9, 8]),
+];
+
+// No letter starting or ending a digit word, so that fillers never complete one.
+const FILLERS: &[u8] = b"abcdjklmpqyz";
+
+fn calibration(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let (mut sum1, mut sum2) = (0, 0);
+    for _ in 0..size {
+        let mut digits = Vec::new(); // (digit, spelled)
+
+        // One token at least is a figure, for part 1 to have an answer.
+        let figure = rng.range(0, 5);
+        for k in 0..rng.range(figure + 1, 8) {
+            for _ in 0..rng.range(0, 4) {
+                input.push(char::from(*rng.pick(FILLERS)));
+            }
+            match if k == figure { 0 } else { rng.range(0, 3) } {
+                0 => {
+                    let d = rng.range(1, 10);
+                    input.push(char::from(b'0' + d as u8));
+                    digits.push((d as u128, false));
+                }
+                1 => {
+                    let d = rng.range(1, 10);
+                    input += DIGIT_WORDS[d as usize - 1];
+                    digits.push((d as u128, true));
+                }
+                _ => {
+                    let (word, ds) = rng.pick(&OVERLAPS);
+                    input += word;
+                    digits.extend(ds.map(|d| (d, true)));
+                }
+            }
+        }
+        input.push('\n');
+
+        let mut figures = digits.iter().filter(|(_, spelled)| !spelled);
+        let first = figures.next().map_or(0, |d| d.0);
+        sum1 += 10 * first + figures.next_back().map_or(first, |d| d.0);
+        sum2 += 10 * digits[0].0 + digits[digits.len() - 1].0;
+    }
+    Generated {
+        input,
+        answers: [answer::<u64>(sum1), answer::<u64>(sum2)],
+    }
+}
+
+fn games(rng: &mut Rng, size: usize) -> Generated {
+    const COLORS: [&str; 3] = ["red", "green", "blue"];
+    const BAG: [u128; 3] = [12, 13, 14];
+
+    let mut input = String::new();
+    let (mut sum1, mut sum2) = (0, 0);
+    for id in 1..=size {
+        let mut max = [0; 3];
+        let draws = (0..rng.range(1, 7)).map(|_| {
+            let mut colors = [0, 1, 2];
+            rng.shuffle(&mut colors);
+            let cubes = colors[..rng.range(1, 4) as usize].iter().map(|&c| {
+                let n = rng.range(1, 21) as u128;
+                max[c] = max[c].max(n);
+                format!("{n} {}", COLORS[c])
+            });
+            cubes.collect::<Vec<_>>().join(", ")
+        });
+        let draws = draws.collect::<Vec<_>>().join("; ");
+        writeln!(input, "Game {id}: {draws}").unwrap();
+
+        if max.iter().zip(BAG).all(|(&m, b)| m <= b) {
+            sum1 += id as u128;
+        }
+        sum2 += max.iter().product::<u128>();
+    }
+    Generated {
+        input,
+        answers: [answer::<u32>(sum1), answer::<u32>(sum2)],
+    }
+}
+
+fn schematic(rng: &mut Rng, size: usize) -> Generated {
+    const WIDTH: usize = 140;
+    const SYMBOLS: &[u8] = b"#$%&*+-/=@****";
+
+    let mut grid = vec![vec![b'.'; WIDTH]; size];
+    for row in &mut grid {
+        let mut c = rng.range(0, 4) as usize;
+        while c < WIDTH {
+            if rng.range(0, 2) == 0 {
+                // Numbers have no leading zero, and end before a non-digit.
+                let len = (rng.range(1, 4) as usize).min(WIDTH - c);
+                row[c] = b'1' + rng.range(0, 9) as u8;
+                for b in &mut row[c + 1..c + len] {
+                    *b = b'0' + rng.range(0, 10) as u8;
+                }
+                c += len;
+                if c < WIDTH && rng.range(0, 3) == 0 {
+                    row[c] = *rng.pick(SYMBOLS);
+                }
+                c += 1;
+            } else {
+                row[c] = *rng.pick(SYMBOLS);
+            }
+            c += rng.range(1, 5) as usize;
+        }
+    }
+
+    // (row, first column, last column, value)
+    let mut numbers = Vec::new();
+    for (r, row) in grid.iter().enumerate() {
+        let mut c = 0;
+        while c < WIDTH {
+            let len = row[c..].iter().take_while(|b| b.is_ascii_digit()).count();
+            if len > 0 {
+                let value = std::str::from_utf8(&row[c..c + len])
+                    .unwrap()
+                    .parse()
+                    .unwrap();
+                numbers.push((r, c, c + len - 1, value));
+            }
+            c += len.max(1);
+        }
+    }
+    let adjacent = |&(r, first, last, _): &(usize, usize, usize, u128), sr: usize, sc: usize| {
+        r.abs_diff(sr) <= 1 && first <= sc + 1 && sc <= last + 1
+    };
+
+    let mut sum1 = 0;
+    for n in &numbers {
+        let (r, first, last, value) = *n;
+        let rows = r.saturating_sub(1)..=(r + 1).min(size - 1);
+        let cols = first.saturating_sub(1)..=(last + 1).min(WIDTH - 1);
+        let symbol = |b: u8| b != b'.' && !b.is_ascii_digit();
+        if rows
+            .flat_map(|sr| cols.clone().map(move |sc| (sr, sc)))
+            .any(|(sr, sc)| symbol(grid[sr][sc]))
+        {
+            sum1 += value;
+        }
+    }
+    let mut sum2 = 0;
+    for (sr, row) in grid.iter().enumerate() {
+        for sc in (0..WIDTH).filter(|&sc| row[sc] == b'*') {
+            // Numbers are sorted by row.
+            let lo = numbers.partition_point(|n| n.0 + 1 < sr);
+            let hi = numbers.partition_point(|n| n.0 <= sr + 1);
+            let parts = numbers[lo..hi].iter().filter(|n| adjacent(n, sr, sc));
+            if let [a, b] = parts.collect::<Vec<_>>()[..] {
+                sum2 += a.3 * b.3;
+            }
+        }
+    }
+
+    let mut input = String::with_capacity(size * (WIDTH + 1));
+    for row in grid {
+        input += std::str::from_utf8(&row).unwrap();
+        input.push('\n');
+    }
+    Generated {
+        input,
+        answers: [answer::<u32>(sum1), answer::<u32>(sum2)],
+    }
+}
+
+fn scratchcards(rng: &mut Rng, size: usize) -> Generated {
+    let mut input = String::new();
+    let mut matches = Vec::with_capacity(size);
+    for id in 1..=size {
+        let mut numbers = (1..100).collect::<Vec<u32>>();
+        rng.shuffle(&mut numbers);
+        // Fewer than one match per card on average, which keeps the count of copies small.
+        let m = match rng.range(0, 10) {
+            0..=5 => 0,
+            k => (k as usize - 5).min(3),
+        }
+        .min(size - id);
+        let (winning, rest) = numbers.split_at(10);
+        let mut have = [&winning[..m], &rest[..25 - m]].concat();
+        rng.shuffle(&mut have);
+
+        let list = |ns: &[u32]| ns.iter().map(|n| format!("{n:>2}")).collect::<Vec<_>>();
+        let (winning, have) = (list(winning).join(" "), list(&have).join(" "));
+        writeln!(input, "Card {id:>3}: {winning} | {have}").unwrap();
+        matches.push(m);
+    }
+
+    let sum1 = matches
+        .iter()
+        .map(|&m| if m == 0 { 0 } else { 1 << (m - 1) })
+        .sum();
+    let mut copies = vec![1u128; size];
+    for (k, &m) in matches.iter().enumerate() {
+        for next in k + 1..=k + m {
+            copies[next] += copies[k];
+        }
+    }
+    Generated {
+        input,
+        answers: [answer::<u32>(sum1), answer::<u64>(copies.iter().sum())],
+    }
+}
+
+pub(crate) const MAP_NAMES: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+fn almanac(rng: &mut Rng, size: usize) -> Generated {
+    const SPAN: u64 = 1 << 32;
+    // Total count of seeds in part 2, which is solved by brute force here.
+    const MAX_SEEDS: u64 = 1_000_000;
+
+    let size = size.max(1);
+    let max_len = (MAX_SEEDS / size as u64).max(1);
+    let seeds = (0..size)
+        .map(|_| (rng.range(0, SPAN), rng.range(1, max_len + 1)))
+        .collect::<Vec<_>>();
+
+    // Sorted (src, dst, len) of each map, with distinct sources.
+    let maps = MAP_NAMES.map(|_| {
+        let mut bounds = (0..2 * size)
+            .map(|_| rng.range(0, SPAN))
+            .collect::<Vec<_>>();
+        bounds.sort_unstable();
+        bounds.dedup();
+        let ranges = bounds
+            .chunks_exact(2)
+            .map(|b| (b[0], rng.range(0, SPAN), b[1] - b[0]));
+        ranges.collect::<Vec<_>>()
+    });
+
+    let mut input = String::from("seeds:");
+    for (start, len) in &seeds {
+        write!(input, " {start} {len}").unwrap();
+    }
+    input += "\n";
+    for (name, ranges) in MAP_NAMES.iter().zip(&maps) {
+        let mut ranges = ranges.clone();
+        rng.shuffle(&mut ranges);
+        write!(input, "\n{name} map:\n").unwrap();
+        for (src, dst, len) in ranges {
+            writeln!(input, "{dst} {src} {len}").unwrap();
+        }
+    }
+
+    let location = |seed: u64| {
+        maps.iter().fold(seed, |v, ranges| {
+            let k = ranges.partition_point(|r| r.0 <= v);
+            match k.checked_sub(1).map(|k| ranges[k]) {
+                Some((src, dst, len)) if v - src < len => dst + (v - src),
+                _ => v,
+            }
+        })
+    };
+    let lowest1 = seeds.iter().flat_map(|&(a, b)| [a, b]).map(location).min();
+    let lowest2 = seeds
+        .iter()
+        .flat_map(|&(start, len)| start..start + len)
+        .map(location)
+        .min();
+    Generated {
+        input,
+        answers: [lowest1, lowest2].map(|l| answer::<u64>(l.unwrap_or_default() as u128)),
+    }
+}
+
+fn races(rng: &mut Rng, size: usize) -> Generated {
+    let races = (0..size.max(1))
+        .map(|_| {
+            let time = rng.range(7, 100);
+            // Below the best distance, so that every race can be won.
+            (time, rng.range(0, time * time / 4))
+        })
+        .collect::<Vec<_>>();
+    let join = |f: fn(&(u64, u64)) -> u64| {
+        let values = races.iter().map(|r| format!("{:>5}", f(r)));
+        values.collect::<String>()
+    };
+    let (times, records) = (join(|r| r.0), join(|r| r.1));
+    let input = format!("Time:    {times}\nDistance:{records}\n");
+
+    let product = races.iter().try_fold(1u128, |p, &(t, d)| {
+        p.checked_mul(ways(t as u128, d as u128))
+    });
+    // Part 2 reads the figures as a single race.
+    let figures = |s: &str| s.replace(' ', "").parse::<u128>().ok();
+    let ways2 = match (figures(&times), figures(&records)) {
+        (Some(t), Some(d)) if t <= u64::MAX as u128 && d <= u64::MAX as u128 => Some(ways(t, d)),
+        _ => None,
+    };
+    Generated {
+        input,
+        answers: [
+            product.and_then(answer::<u32>),
+            ways2.and_then(answer::<u64>),
+        ],
+    }
+}
+
+// Count of button presses beating the record `d` in a race of `t` milliseconds, from the roots
+// of p * (t - p) = d.
+fn ways(t: u128, d: u128) -> u128 {
+    let Some(disc) = (t * t).checked_sub(4 * d) else {
+        return 0;
+    };
+    let wins = |p: u128| p * (t - p) > d;
+    let mut p = (t - disc.isqrt().min(t)) / 2;
+    while p > 0 && wins(p - 1) {
+        p -= 1;
+    }
+    while p <= t / 2 && !wins(p) {
+        p += 1;
+    }
+    if p > t / 2 {
+        0
+    } else {
+        t + 1 - 2 * p
+    }
+}
+
+fn hands(rng: &mut Rng, size: usize) -> Generated {
+    const CARDS: &[u8] = b"23456789TJQKA";
+    const JOKER_ORDER: &[u8] = b"J23456789TQKA";
+
+    let size = size.min(CARDS.len().pow(5));
+    let mut seen = HashSet::with_capacity(size);
+    let mut hands = Vec::with_capacity(size);
+    while hands.len() < size {
+        let hand: [u8; 5] = std::array::from_fn(|_| *rng.pick(CARDS));
+        if seen.insert(hand) {
+            hands.push((hand, rng.range(1, 1001) as u128));
+        }
+    }
+    let input = hands
+        .iter()
+        .map(|(hand, bid)| format!("{} {bid}\n", std::str::from_utf8(hand).unwrap()))
+        .collect();
+
+    let kind = |hand: &[u8; 5]| {
+        let mut counts = CARDS
+            .iter()
+            .map(|&c| hand.iter().filter(|&&h| h == c).count())
+            .collect::<Vec<_>>();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        match counts[..2] {
+            [5, _] => 6,
+            [4, _] => 5,
+            [3, 2] => 4,
+            [3, _] => 3,
+            [2, 2] => 2,
+            [2, _] => 1,
+            _ => 0,
+        }
+    };
+    let with_jokers = |hand: &[u8; 5]| {
+        let replaced = CARDS
+            .iter()
+            .map(|&c| hand.map(|h| if h == b'J' { c } else { h }));
+        replaced.map(|h| kind(&h)).max().unwrap()
+    };
+    let winnings = |kind: &dyn Fn(&[u8; 5]) -> u8, order: &[u8]| {
+        let strength = |c| order.iter().position(|&o| o == c).unwrap();
+        let mut ranked = hands.clone();
+        ranked.sort_by_key(|(hand, _)| (kind(hand), hand.map(strength)));
+        ranked
+            .iter()
+            .zip(1..)
+            .map(|((_, bid), rank)| bid * rank)
+            .sum()
+    };
+    Generated {
+        input,
+        answers: [
+            answer::<u32>(winnings(&kind, CARDS)),
+            answer::<u32>(winnings(&with_jokers, JOKER_ORDER)),
+        ],
+    }
+}
+
+fn network(rng: &mut Rng, size: usize) -> Generated {
+    const NAME_CHARS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    // Names of 3 characters not ending with 'A' or 'Z' limit the count of nodes.
+    const MAX_NODES: usize = 36 * 36 * 34;
+
+    // Each ghost walks a chain of p * L nodes, back to its second node after its end, where L is
+    // the count of instructions and the p are distinct primes. The ghosts are then all at their
+    // ends after L times the product of the p steps.
+    let size = size.clamp(6, MAX_NODES);
+    let ghosts = (size / 200).clamp(1, 6);
+    let per_ghost = size / ghosts - 1;
+    let len = prime_below(per_ghost.isqrt().max(3));
+    // Primes below the period filling a chain, largest first, then above it if they run out.
+    let p = per_ghost / len + 1;
+    let periods = (2..p)
+        .rev()
+        .chain(p..)
+        .filter(|&k| k != len && is_prime(k))
+        .take(ghosts)
+        .collect::<Vec<_>>();
+    let instructions = (0..len).map(|_| *rng.pick(b"LR")).collect::<Vec<_>>();
+
+    let mut names = (0..MAX_NODES).map(|k| {
+        let last = NAME_CHARS.iter().filter(|&&c| c != b'A' && c != b'Z');
+        [k / 34 / 36, k / 34 % 36]
+            .map(|d| NAME_CHARS[d])
+            .into_iter()
+            .chain(last.copied().nth(k % 34))
+    });
+    let mut nodes = Vec::with_capacity(size);
+    let mut children = Vec::with_capacity(size);
+    for (g, p) in periods.iter().enumerate() {
+        let (start, end) = match g {
+            0 => ("AAA".to_string(), "ZZZ".to_string()),
+            g => (format!("{g}{g}A"), format!("{g}{g}Z")),
+        };
+        let first = nodes.len();
+        nodes.push(start);
+        for _ in 1..p * len {
+            nodes.push(String::from_utf8(names.next().unwrap().collect()).unwrap());
+        }
+        nodes.push(end);
+        // The node at step t leads to the node at step t + 1, and the end to step 1.
+        for t in 0..=p * len {
+            let next = first + if t == p * len { 1 } else { t + 1 };
+            children.push((next, instructions[t % len]));
+        }
+    }
+
+    let mut lines = (0..nodes.len())
+        .map(|k| {
+            let (next, dir) = children[k];
+            let decoy = rng.range(0, nodes.len() as u64) as usize;
+            let (left, right) = if dir == b'L' {
+                (next, decoy)
+            } else {
+                (decoy, next)
+            };
+            format!("{} = ({}, {})\n", nodes[k], nodes[left], nodes[right])
+        })
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut lines);
+    let input = format!(
+        "{}\n\n{}",
+        String::from_utf8(instructions).unwrap(),
+        lines.concat()
+    );
+
+    let steps = periods.iter().fold(len as u128, |acc, &p| {
+        let p = p as u128 * len as u128;
+        acc / gcd(acc, p) * p
+    });
+    Generated {
+        input,
+        answers: [
+            answer::<u32>((periods[0] * len) as u128),
+            answer::<u64>(steps),
+        ],
+    }
+}
+
+fn is_prime(k: usize) -> bool {
+    (2..)
+        .take_while(|d| d * d <= k)
+        .all(|d| !k.is_multiple_of(d))
+}
+
+// Largest prime below `n`, or 2.
+fn prime_below(n: usize) -> usize {
+    (2..n).rev().find(|&k| is_prime(k)).unwrap_or(2)
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[test]
+fn generated_answers() {
+    use crate::registry::SOLVERS;
+
+    for s in &SOLVERS {
+        for seed in 0..3 {
+            // Days scaffolded by `aoc new` have no generator until one is written here.
+            let Some(generated) = generate(s.day, 200, seed) else {
+                continue;
+            };
+            let expected = &generated.answers[s.part as usize - 1];
+            let run = s.solve(&mut generated.input.as_bytes());
+            match (expected, run) {
+                (Some(a), Ok(run)) => assert_eq!(&run.answer, a, "day {} part {}", s.day, s.part),
+                (None, Err(_)) => {}
+                (a, r) => panic!(
+                    "day {} part {} seed {seed}: expected {a:?}, got {:?}",
+                    s.day,
+                    s.part,
+                    r.map(|r| r.answer)
+                ),
+            }
+            let bytes = s.solve_bytes(generated.input.as_bytes());
+            assert_eq!(
+                bytes.map(|r| r.answer).ok(),
+                expected.clone(),
+                "day {} part {} seed {seed}, in memory",
+                s.day,
+                s.part
+            );
+        }
+    }
+}
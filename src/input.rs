@@ -0,0 +1,137 @@
+use crate::mmap::Mmap;
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::path::PathBuf;
+
+/// Environment variable pointing at a directory laid out like this repository, i.e. holding one
+/// `dayNN/input` file per day.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+#[derive(Clone)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// Resolves the input of the given day from an optional command-line argument.
+    ///
+    /// `-` selects stdin, any other value is a file path. Without argument, the input is read from
+    /// `$AOC_INPUT_DIR/dayNN/input`, or from the `dayNN/input` file of this repository.
+    pub fn resolve(day: u8, arg: Option<&str>) -> Self {
+        match arg {
+            Some("-") => Source::Stdin,
+            Some(path) => Source::File(PathBuf::from(path)),
+            None => Source::File(dir().join(format!("day{day:02}")).join("input")),
+        }
+    }
+
+    pub fn open(&self) -> Result<Box<dyn io::Read>, Error> {
+        match self {
+            Source::Stdin => Ok(Box::new(io::stdin().lock())),
+            Source::File(path) => match File::open(path) {
+                Ok(f) => Ok(Box::new(f)),
+                Err(e) => Err(Error {
+                    path: path.clone(),
+                    source: e,
+                }),
+            },
+        }
+    }
+
+    /// The whole input in memory: files are mapped, stdin is read.
+    pub fn bytes(&self) -> Result<Bytes, crate::Error> {
+        match self {
+            Source::Stdin => Ok(Bytes::Read(crate::read_all(io::stdin().lock())?)),
+            Source::File(path) => {
+                let map = File::open(path).and_then(|f| Mmap::map(&f));
+                map.map(Bytes::Mapped).map_err(|e| {
+                    Error {
+                        path: path.clone(),
+                        source: e,
+                    }
+                    .into()
+                })
+            }
+        }
+    }
+}
+
+pub enum Bytes {
+    Mapped(Mmap),
+    Read(Vec<u8>),
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Bytes::Mapped(m) => m,
+            Bytes::Read(v) => v,
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Stdin => write!(f, "-"),
+            Source::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Directory holding the `dayNN/input` files: `$AOC_INPUT_DIR`, or the root of this repository.
+pub fn dir() -> PathBuf {
+    env::var_os(INPUT_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")))
+}
+
+/// Opens the input of the given day, as designated by the first argument of the running program.
+pub fn from_args(day: u8) -> Result<Box<dyn io::Read>, Error> {
+    Source::resolve(day, env::args().nth(1).as_deref()).open()
+}
+
+#[derive(Debug)]
+pub struct Error {
+    path: PathBuf,
+    source: io::Error,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cannot open input file {}: {}",
+            self.path.display(),
+            self.source
+        )?;
+        if self.source.kind() == io::ErrorKind::NotFound {
+            write!(
+                f,
+                " (pass the path of the input as argument, `-` to read from stdin, or set \
+                 {INPUT_DIR_VAR} to a directory containing dayNN/input files)"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[test]
+fn resolve_from_arg() {
+    assert!(matches!(Source::resolve(1, Some("-")), Source::Stdin));
+    assert!(
+        matches!(Source::resolve(1, Some("in.txt")), Source::File(p) if p.as_os_str() == "in.txt")
+    );
+}
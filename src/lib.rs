@@ -0,0 +1,112 @@
+// Allows the day modules, which are also compiled as standalone binaries, to refer to this crate
+// by name in both contexts.
+extern crate self as adventofcode2023;
+
+use std::fmt::Display;
+use std::io;
+
+pub mod alloc;
+pub mod answers;
+pub mod automaton;
+pub mod bench;
+pub mod differential;
+mod error;
+pub mod examples;
+pub mod fetch;
+pub mod fuzz;
+pub mod gen;
+pub mod input;
+pub mod json;
+pub mod lines;
+pub mod mmap;
+pub mod output;
+pub mod pool;
+pub mod registry;
+pub mod rng;
+pub mod scaffold;
+pub mod status;
+pub mod toml;
+pub mod watch;
+
+pub use error::{Error, ParseError};
+
+#[path = "../day01/mod.rs"]
+pub mod day01;
+#[path = "../day02/mod.rs"]
+pub mod day02;
+#[path = "../day03/mod.rs"]
+pub mod day03;
+#[path = "../day04/mod.rs"]
+pub mod day04;
+#[path = "../day05/mod.rs"]
+pub mod day05;
+#[path = "../day06/mod.rs"]
+pub mod day06;
+#[path = "../day07/mod.rs"]
+pub mod day07;
+#[path = "../day08/mod.rs"]
+pub mod day08;
+
+/// Solution to both parts of a day's puzzle.
+///
+/// The input is parsed once and can be shared by both parts.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse<R: io::Read>(r: R) -> Result<Self::Input, Error>;
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, Error>;
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, Error>;
+
+    /// Parses the whole input in memory, which days override to split its lines in place instead
+    /// of copying them through a reader. Both paths parse the same bytes, so that they fail alike.
+    fn parse_bytes(input: &[u8]) -> Result<Self::Input, Error> {
+        Self::parse(input)
+    }
+
+    /// Solves part 1 straight from the reader, which days whose parts read their input line by
+    /// line override so that memory use does not grow with the input.
+    fn stream1<R: io::Read>(r: R) -> Result<Self::Answer1, Error> {
+        Self::part1(&Self::parse(r)?)
+    }
+
+    /// Solves part 2 straight from the reader, like [`Solution::stream1`].
+    fn stream2<R: io::Read>(r: R) -> Result<Self::Answer2, Error> {
+        Self::part2(&Self::parse(r)?)
+    }
+
+    /// Solves part 1 from the whole input in memory, which days override to scan it without
+    /// parsing it first.
+    fn part1_bytes(input: &[u8]) -> Result<Self::Answer1, Error> {
+        Self::part1(&Self::parse_bytes(input)?)
+    }
+
+    /// Solves part 2 from the whole input in memory, like [`Solution::part1_bytes`].
+    fn part2_bytes(input: &[u8]) -> Result<Self::Answer2, Error> {
+        Self::part2(&Self::parse_bytes(input)?)
+    }
+}
+
+/// Reads the whole input in memory.
+pub fn read_all<R: io::Read>(mut r: R) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    r.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+#[test]
+fn parse_once_solve_both() {
+    use day06::Day06;
+
+    let input = "\
+        Time:      7  15   30\n\
+        Distance:  9  40  200\n\
+        "
+    .as_bytes();
+    let input = Day06::parse(input).unwrap();
+    assert_eq!(Day06::part1(&input).unwrap(), 288);
+    assert_eq!(Day06::part2(&input).unwrap(), 71503);
+}
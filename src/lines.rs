@@ -0,0 +1,185 @@
+//! Line reader reusing a single buffer, so that solvers stream inputs of any size in constant
+//! memory instead of allocating a `String` per line like `BufRead::lines()`, and line splitting in
+//! place of inputs already in memory.
+//!
+//! Lines are bytes, which the parsers of the days read without checking that they are UTF-8 first:
+//! their puzzle inputs are ASCII, and any other byte is reported where it is found.
+
+use std::io;
+use std::io::{BufRead, BufReader};
+
+pub struct Lines<R> {
+    reader: BufReader<R>,
+    buf: Vec<u8>,
+    index: usize,
+}
+
+impl<R: io::Read> Lines<R> {
+    pub fn new(r: R) -> Self {
+        Lines {
+            reader: BufReader::new(r),
+            buf: Vec::new(),
+            index: 0,
+        }
+    }
+
+    /// Next line and its index, without the line ending. The line is borrowed from the buffer
+    /// overwritten by the following call.
+    pub fn next_line(&mut self) -> Option<io::Result<(usize, &[u8])>> {
+        self.buf.clear();
+        match self.reader.read_until(b'\n', &mut self.buf) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        // Same line endings as `BufRead::lines()`: "\n" or "\r\n".
+        if self.buf.last() == Some(&b'\n') {
+            self.buf.pop();
+            if self.buf.last() == Some(&b'\r') {
+                self.buf.pop();
+            }
+        }
+        let i = self.index;
+        self.index += 1;
+        Some(Ok((i, &self.buf)))
+    }
+
+    /// Folds the lines and their index, stopping at the first error.
+    pub fn try_fold<T, E: From<io::Error>>(
+        mut self,
+        init: T,
+        mut f: impl FnMut(T, (usize, &[u8])) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut acc = init;
+        while let Some(l) = self.next_line() {
+            acc = f(acc, l?)?;
+        }
+        Ok(acc)
+    }
+}
+
+/// Lines of an input held in memory and their index, split like [`Lines`] does.
+pub fn split(input: &[u8]) -> Split<'_> {
+    Split {
+        rest: input,
+        index: 0,
+    }
+}
+
+pub struct Split<'a> {
+    rest: &'a [u8],
+    index: usize,
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = (usize, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let line = match self.rest.iter().position(|&b| b == b'\n') {
+            Some(j) => {
+                let line = &self.rest[..j];
+                self.rest = &self.rest[j + 1..];
+                line.strip_suffix(b"\r").unwrap_or(line)
+            }
+            None => std::mem::take(&mut self.rest),
+        };
+        let i = self.index;
+        self.index += 1;
+        Some((i, line))
+    }
+}
+
+/// Either kind of lines, for parsers which take lines one by one rather than folding them.
+pub trait LineSource {
+    fn next_line(&mut self) -> Option<io::Result<(usize, &[u8])>>;
+
+    /// Parses every line and its index with `f`, stopping at the first error.
+    fn parse_each<T, E: From<io::Error>>(
+        mut self,
+        mut f: impl FnMut(usize, &[u8]) -> Result<T, E>,
+    ) -> Result<Vec<T>, E>
+    where
+        Self: Sized,
+    {
+        let mut parsed = Vec::new();
+        while let Some(l) = self.next_line() {
+            let (i, line) = l?;
+            parsed.push(f(i, line)?);
+        }
+        Ok(parsed)
+    }
+}
+
+impl<R: io::Read> LineSource for Lines<R> {
+    fn next_line(&mut self) -> Option<io::Result<(usize, &[u8])>> {
+        Lines::next_line(self)
+    }
+}
+
+impl LineSource for Split<'_> {
+    fn next_line(&mut self) -> Option<io::Result<(usize, &[u8])>> {
+        self.next().map(Ok)
+    }
+}
+
+/// Text of a line, for the parsers which need one, failing like `BufRead::lines()` if it is not
+/// UTF-8.
+pub fn text(line: &[u8]) -> io::Result<&str> {
+    std::str::from_utf8(line).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        )
+    })
+}
+
+/// Number written in decimal by `digits`, if they are all ASCII digits and it fits in `T`.
+pub fn number<T: TryFrom<u64>>(digits: &[u8]) -> Option<T> {
+    if digits.is_empty() {
+        return None;
+    }
+    let n = digits.iter().try_fold(0u64, |n, &d| {
+        if !d.is_ascii_digit() {
+            return None;
+        }
+        n.checked_mul(10)?.checked_add(u64::from(d - b'0'))
+    })?;
+    T::try_from(n).ok()
+}
+
+/// Parts of `line` before and after the first `separator`, like `str::split_once`.
+pub fn split_once<'a>(line: &'a [u8], separator: &[u8]) -> Option<(&'a [u8], &'a [u8])> {
+    let j = line.windows(separator.len()).position(|w| w == separator)?;
+    Some((&line[..j], &line[j + separator.len()..]))
+}
+
+#[test]
+fn same_lines_as_bufread() {
+    let input = "a\r\nb\n\n\rc\r\nd".as_bytes();
+    let mut expected = BufReader::new(input).lines().enumerate();
+    let mut lines = Lines::new(input);
+    while let Some(l) = lines.next_line() {
+        let (i, l) = l.unwrap();
+        assert_eq!(
+            Some((i, text(l).unwrap().to_string())),
+            expected.next().map(|(i, e)| (i, e.unwrap()))
+        );
+    }
+    assert!(expected.next().is_none());
+
+    let split = split(input).map(|l| l.1).collect::<Vec<_>>();
+    assert_eq!(split, [&b"a"[..], b"b", b"", b"\rc", b"d"]);
+    assert!(text(b"\xff").is_err());
+}
+
+#[test]
+fn numbers() {
+    assert_eq!(number::<u32>(b"4294967295"), Some(u32::MAX));
+    assert_eq!(number::<u32>(b"4294967296"), None);
+    assert_eq!(number::<u64>(b"+1"), None);
+    assert_eq!(number::<u64>(b""), None);
+    assert_eq!(split_once(b"a-to-b", b"-to-"), Some((&b"a"[..], &b"b"[..])));
+}
@@ -0,0 +1,617 @@
+use adventofcode2023::alloc;
+use adventofcode2023::answers::{self, Answer, Outcome};
+use adventofcode2023::bench::{self, Baseline};
+use adventofcode2023::fetch::{self, Config, Fetched};
+use adventofcode2023::input::Source;
+use adventofcode2023::output::{self, Failure, Format, Record};
+use adventofcode2023::pool;
+use adventofcode2023::registry::{self, Solver, SOLVERS};
+use adventofcode2023::watch::{self, Watcher};
+use adventofcode2023::{examples, gen, scaffold, status};
+use adventofcode2023::{read_all, Error};
+use std::env;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "\
+usage: aoc run <day> <part> [<input file> | -] [--format json|csv|text]
+               [--stream | --mmap]
+       aoc run --all [--jobs <n>] [--format json|csv|text] [--stream | --mmap]
+       aoc bench [<day> [<part>]] [--warmup <n>] [--iterations <n>] [--bytes]
+                 [--baseline <file>] [--save] [--threshold <percent>]
+       aoc verify [<day>] [--answers <file>]
+       aoc status [--answers <file>] [--baseline <file>]
+       aoc fetch <day> [--config <file>]
+       aoc new <day>
+       aoc watch <day>
+       aoc gen <day> [--size <n>] [--seed <n>]
+       aoc list";
+
+const BASELINE_FILE: &str = "bench_baseline.json";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// How `aoc run` gets the input to the solver.
+#[derive(Clone, Copy)]
+enum Mode {
+    Read,   // all of it in memory, then parsed
+    Stream, // read as the solver goes, instead of being held in memory first
+    Mmap,   // mapped in memory, and its lines split in place
+}
+
+fn solve<'a>(s: &'a Solver, src: &Source, mode: Mode) -> Result<Record<'a>, Error> {
+    match mode {
+        Mode::Read => {
+            let input = read_all(src.open()?)?;
+            Ok(Record {
+                solver: s,
+                run: s.solve(&mut &input[..])?,
+                input_hash: output::hash(&input),
+            })
+        }
+        Mode::Stream => {
+            let mut r = output::Hashing::new(src.open()?);
+            let run = s.stream(&mut r)?;
+            Ok(Record {
+                solver: s,
+                run,
+                input_hash: r.finish()?,
+            })
+        }
+        Mode::Mmap => {
+            let input = src.bytes()?;
+            Ok(Record {
+                solver: s,
+                run: s.solve_bytes(&input)?,
+                input_hash: output::hash(&input),
+            })
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args[..] {
+        ["list"] => {
+            for s in &SOLVERS {
+                println!("day{:02} part {}: {}", s.day, s.part, s.name);
+            }
+            ExitCode::SUCCESS
+        }
+        ["run", ref opts @ ..] => run(opts),
+        ["bench", ref opts @ ..] => run_bench(opts),
+        ["verify", ref opts @ ..] => verify(opts),
+        ["status", ref opts @ ..] => show_status(opts),
+        ["new", day] => new_day(day),
+        ["watch", day] => watch(day),
+        ["gen", day, ref opts @ ..] => generate(day, opts),
+        ["fetch", day] => fetch_input(day, Config::path()),
+        ["fetch", day, "--config", path] => fetch_input(day, PathBuf::from(path)),
+        _ => usage(),
+    }
+}
+
+fn run(args: &[&str]) -> ExitCode {
+    // Without format, a single answer is printed alone and all of them in a table.
+    let mut format = None;
+    let mut mode = Mode::Read;
+    let mut jobs = thread::available_parallelism().map_or(1, usize::from);
+    let mut positional = Vec::with_capacity(3);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match *arg {
+            "--format" => match args.next().map(|f| f.parse::<Format>()) {
+                Some(Ok(f)) => format = Some(f),
+                Some(Err(e)) => {
+                    eprintln!("{e}");
+                    return usage();
+                }
+                None => return usage(),
+            },
+            "--jobs" => match value(&mut args) {
+                Some(n) if n > 0 => jobs = n,
+                _ => return usage(),
+            },
+            "--stream" => mode = Mode::Stream,
+            "--mmap" => mode = Mode::Mmap,
+            arg => positional.push(arg),
+        }
+    }
+
+    let all = positional == ["--all"];
+    let solvers = match positional[..] {
+        ["--all"] => SOLVERS.iter().map(|s| (s, None)).collect::<Vec<_>>(),
+        [day, part] | [day, part, _] => {
+            let (Ok(day), Ok(part)) = (day.parse::<u8>(), part.parse::<u8>()) else {
+                return usage();
+            };
+            let Some(s) = registry::find(day, part) else {
+                eprintln!("no solution for day {day} part {part}");
+                return ExitCode::FAILURE;
+            };
+            vec![(s, positional.get(2).copied())]
+        }
+        _ => return usage(),
+    };
+
+    pool::quiet_panics();
+    let begin = Instant::now();
+    let results = pool::map(&solvers, jobs, |(s, arg)| {
+        let begin = Instant::now();
+        let record = solve(s, &Source::resolve(s.day, *arg), mode);
+        (record, begin.elapsed())
+    });
+    let wall = begin.elapsed();
+
+    let mut status = ExitCode::SUCCESS;
+    let mut rows = Vec::with_capacity(results.len());
+    // Records of the solvers in order, failed ones included.
+    let mut records = Vec::with_capacity(results.len());
+    let mut cpu = Duration::ZERO;
+    for ((s, _), result) in solvers.iter().zip(results) {
+        let failure = |status, message: String| {
+            eprintln!("day{:02} part {}: {message}", s.day, s.part);
+            Err(Failure {
+                solver: s,
+                status,
+                message,
+            })
+        };
+        let (answer, time, memory, outcome) = match result {
+            Ok((Ok(record), time)) => {
+                let (answer, memory) = (record.run.answer.clone(), record.run.memory);
+                records.push(Ok(record));
+                (answer, Some(time), memory, "ok")
+            }
+            Ok((Err(e), time)) => {
+                records.push(failure("error", e.to_string()));
+                (String::new(), Some(time), None, "error")
+            }
+            Err(panic) => {
+                records.push(failure("panic", panic));
+                (String::new(), None, None, "panic")
+            }
+        };
+        if outcome != "ok" {
+            status = ExitCode::FAILURE;
+        }
+        cpu += time.unwrap_or_default();
+        let mut row = vec![
+            format!("day{:02}", s.day),
+            s.part.to_string(),
+            answer,
+            time.map_or_else(String::new, |t| format!("{t:.1?}")),
+        ];
+        if alloc::ENABLED {
+            row.extend(match memory {
+                Some(m) => [m.allocations, m.bytes, m.peak].map(|n| n.to_string()),
+                None => Default::default(),
+            });
+        }
+        row.push(outcome.to_string());
+        rows.push(row);
+    }
+
+    let mut header = vec!["day", "part", "answer", "time"];
+    if alloc::ENABLED {
+        header.extend(["allocations", "bytes", "peak bytes"]);
+    }
+    header.push("status");
+
+    let mut out = io::stdout().lock();
+    let written = match format {
+        Some(f) => output::header(&mut out, f).and_then(|()| {
+            records.iter().try_for_each(|r| match r {
+                Ok(r) => output::write(&mut out, f, r),
+                Err(failure) => output::write_failure(&mut out, f, failure),
+            })
+        }),
+        None if all => print_table(&mut out, &header, &rows)
+            .and_then(|()| writeln!(out, "wall-clock {wall:.1?}, summed over solvers {cpu:.1?}")),
+        None => records
+            .iter()
+            .flatten()
+            .try_for_each(|r| writeln!(out, "{}", r.run.answer)),
+    };
+    if written.is_err() {
+        return ExitCode::FAILURE;
+    }
+    status
+}
+
+fn print_table(out: &mut impl Write, header: &[&str], rows: &[Vec<String>]) -> io::Result<()> {
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<_>>();
+    for row in rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let header = header.iter().map(|h| h.to_string()).collect();
+    let separator = widths.iter().map(|&w| "-".repeat(w)).collect();
+    for cells in [&header, &separator].into_iter().chain(rows) {
+        let cells = cells
+            .iter()
+            .zip(&widths)
+            .map(|(c, w)| format!("{c:<w$}"))
+            .collect::<Vec<_>>();
+        writeln!(out, "{}", cells.join("  ").trim_end())?;
+    }
+    Ok(())
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
+}
+
+// Parses the value of a command-line option.
+fn value<'a, T: FromStr>(args: &mut impl Iterator<Item = &'a &'a str>) -> Option<T> {
+    args.next().and_then(|v| v.parse().ok())
+}
+
+fn run_bench(args: &[&str]) -> ExitCode {
+    let mut opts = bench::Options::default();
+    let mut baseline_path = PathBuf::from(BASELINE_FILE);
+    let mut save = false;
+    let mut threshold = 10.0;
+    let mut selection = Vec::with_capacity(2);
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match *arg {
+            "--warmup" => value(&mut args).map(|v| opts.warmup = v).is_some(),
+            "--iterations" => value(&mut args).map(|v| opts.iterations = v).is_some(),
+            "--baseline" => value(&mut args).map(|v| baseline_path = v).is_some(),
+            "--threshold" => value(&mut args).map(|v| threshold = v).is_some(),
+            "--save" => {
+                save = true;
+                true
+            }
+            "--bytes" => {
+                opts.bytes = true;
+                true
+            }
+            v => v.parse::<u8>().map(|v| selection.push(v)).is_ok() && selection.len() <= 2,
+        };
+        if !ok {
+            return usage();
+        }
+    }
+
+    // Saving replaces the timings of the measured parts only, the others are kept.
+    let baseline = if !baseline_path.exists() {
+        None
+    } else {
+        match Baseline::load(&baseline_path) {
+            Ok(b) => Some(b),
+            Err(e) => {
+                eprintln!("cannot load baseline {}: {e}", baseline_path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    let mut reports = Vec::with_capacity(SOLVERS.len());
+    for s in SOLVERS.iter().filter(|s| match selection[..] {
+        [day] => s.day == day,
+        [day, part] => s.day == day && s.part == part,
+        _ => true,
+    }) {
+        let report = Source::resolve(s.day, None)
+            .open()
+            .map_err(Error::from)
+            .and_then(|r| Ok(read_all(r)?))
+            .and_then(|input| bench::run(s, &input, &opts));
+        let report = match report {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("day{:02} part {}: {e}", s.day, s.part);
+                status = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        print!(
+            "day{:02} part {}: parse min {:?} median {:?} p95 {:?}, solve min {:?} median {:?} p95 {:?}",
+            s.day,
+            s.part,
+            report.parse.min,
+            report.parse.median,
+            report.parse.p95,
+            report.solve.min,
+            report.solve.median,
+            report.solve.p95,
+        );
+        let compared = baseline.as_ref().filter(|_| !save);
+        match compared.and_then(|b| b.change(&report)) {
+            Some((parse, solve)) => {
+                let show = |change: Option<f64>| match change {
+                    Some(c) => format!("{:+.0}%", c * 100.0),
+                    None => format!("under {:?}", bench::MIN_COMPARED),
+                };
+                print!(
+                    ", vs baseline: parse {}, solve {}",
+                    show(parse),
+                    show(solve)
+                );
+                if [parse, solve]
+                    .into_iter()
+                    .flatten()
+                    .any(|c| c * 100.0 > threshold)
+                {
+                    print!(" REGRESSION");
+                    status = ExitCode::FAILURE;
+                }
+                println!();
+            }
+            None => println!(),
+        }
+        reports.push(report);
+    }
+
+    if save {
+        let mut baseline = baseline.unwrap_or_default();
+        for report in &reports {
+            baseline.record(report);
+        }
+        if let Err(e) = baseline.save(&baseline_path) {
+            eprintln!("cannot save baseline {}: {e}", baseline_path.display());
+            return ExitCode::FAILURE;
+        }
+        println!("saved baseline to {}", baseline_path.display());
+    }
+    status
+}
+
+fn verify(args: &[&str]) -> ExitCode {
+    let mut path: Option<PathBuf> = None;
+    let mut day = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match *arg {
+            "--answers" => value(&mut args).map(|v| path = Some(v)).is_some(),
+            v => day.is_none() && v.parse::<u8>().map(|v| day = Some(v)).is_ok(),
+        };
+        if !ok {
+            return usage();
+        }
+    }
+
+    let Some(answers) = load_answers(path) else {
+        return ExitCode::FAILURE;
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for s in SOLVERS.iter().filter(|s| day.is_none_or(|d| s.day == d)) {
+        let mut recorded = answers
+            .iter()
+            .filter(|a| a.day == s.day && a.part == s.part)
+            .peekable();
+        if recorded.peek().is_none() {
+            println!("day{:02} part {}: missing", s.day, s.part);
+            missing += 1;
+        }
+        for a in recorded {
+            match a.check(s) {
+                Outcome::Pass => {
+                    println!("day{:02} part {}: pass ({})", s.day, s.part, a.input);
+                    passed += 1;
+                }
+                Outcome::Fail { found } => {
+                    println!(
+                        "day{:02} part {}: FAIL ({}): expected {}, found {found}",
+                        s.day, s.part, a.input, a.expected
+                    );
+                    failed += 1;
+                }
+                Outcome::Error(e) => {
+                    println!("day{:02} part {}: FAIL ({}): {e}", s.day, s.part, a.input);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+// Loads the given answers file, or the default one. Without the latter, every answer is missing.
+fn load_answers(path: Option<PathBuf>) -> Option<Vec<Answer>> {
+    let answers = match path {
+        Some(path) => answers::load(&path).map_err(|e| (path, e)),
+        None => {
+            let path = answers::path();
+            match answers::load(&path) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+                r => r.map_err(|e| (path, e)),
+            }
+        }
+    };
+    match answers {
+        Ok(answers) => Some(answers),
+        Err((path, e)) => {
+            eprintln!("cannot load answers {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+fn show_status(args: &[&str]) -> ExitCode {
+    let mut answers_path = None;
+    let mut baseline_path = PathBuf::from(BASELINE_FILE);
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let ok = match *arg {
+            "--answers" => value(&mut args).map(|v| answers_path = Some(v)).is_some(),
+            "--baseline" => value(&mut args).map(|v| baseline_path = v).is_some(),
+            _ => false,
+        };
+        if !ok {
+            return usage();
+        }
+    }
+
+    let Some(answers) = load_answers(answers_path) else {
+        return ExitCode::FAILURE;
+    };
+    // Without a baseline, no timing is shown.
+    let baseline = match Baseline::load(&baseline_path) {
+        Ok(b) => Some(b),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => {
+            eprintln!("cannot load baseline {}: {e}", baseline_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    print!(
+        "{}",
+        status::render(&status::collect(&answers, baseline.as_ref()))
+    );
+    ExitCode::SUCCESS
+}
+
+// Prints a generated input, and its expected answers on stderr to keep stdout pipeable.
+fn generate(day: &str, args: &[&str]) -> ExitCode {
+    let Ok(day @ 1..=25) = day.parse::<u8>() else {
+        return usage();
+    };
+    let mut size = 1000;
+    let mut seed = 2023;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let parsed = match *arg {
+            "--size" => value(&mut args).map(|v| size = v),
+            "--seed" => value(&mut args).map(|v| seed = v),
+            _ => None,
+        };
+        if parsed.is_none() {
+            return usage();
+        }
+    }
+
+    let Some(generated) = gen::generate(day, size, seed) else {
+        eprintln!("no generator for day {day}");
+        return ExitCode::FAILURE;
+    };
+    if let Err(e) = io::stdout().lock().write_all(generated.input.as_bytes()) {
+        eprintln!("cannot write the input: {e}");
+        return ExitCode::FAILURE;
+    }
+    for (part, answer) in (1..).zip(&generated.answers) {
+        eprintln!("part {part}: {}", answer.as_deref().unwrap_or("unknown"));
+    }
+    ExitCode::SUCCESS
+}
+
+fn fetch_input(day: &str, config: PathBuf) -> ExitCode {
+    let Ok(day @ 1..=25) = day.parse::<u8>() else {
+        return usage();
+    };
+    let fetched = Config::load(&config)
+        .map_err(|e| format!("cannot load config {}: {e}", config.display()))
+        .and_then(|c| {
+            let backend = c.backend().map_err(|e| e.to_string())?;
+            fetch::fetch(&c, &*backend, day).map_err(|e| e.to_string())
+        });
+    match fetched {
+        Ok(Fetched::Cached(path)) => println!("day{day:02}: cached in {}", path.display()),
+        Ok(Fetched::Downloaded(path)) => println!("day{day:02}: downloaded to {}", path.display()),
+        Err(e) => {
+            eprintln!("day{day:02}: {e}");
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn new_day(day: &str) -> ExitCode {
+    let Ok(day @ 1..=25) = day.parse::<u8>() else {
+        return usage();
+    };
+    match scaffold::new_day(Path::new(env!("CARGO_MANIFEST_DIR")), day) {
+        Ok(()) => {
+            println!("created day{day:02}, run it with `aoc run {day} 1`");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("cannot create day{day:02}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn watch(day: &str) -> ExitCode {
+    let Ok(day @ 1..=25) = day.parse::<u8>() else {
+        return usage();
+    };
+    if registry::find(day, 1).is_none() {
+        eprintln!("no solution for day {day}");
+        return ExitCode::FAILURE;
+    }
+
+    let mut watcher = Watcher::new(watch::paths(day));
+    let mut answers: [Option<String>; 2] = [None, None];
+    loop {
+        // The solver may have changed, so it is rebuilt and run by a new process.
+        let examples = examples::dir(day).join(answers::FILE);
+        if examples.exists() {
+            println!("day{day:02} examples:");
+            let answers = examples.to_string_lossy();
+            let _ = aoc(&["verify", &day.to_string(), "--answers", &answers]).status();
+        }
+        for (part, previous) in (1..=2).zip(&mut answers) {
+            let out = match aoc(&["run", &day.to_string(), &part.to_string()]).output() {
+                Ok(out) if out.status.success() => out,
+                Ok(out) => {
+                    eprint!("{}", String::from_utf8_lossy(&out.stderr));
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("cannot run cargo: {e}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            let answer = String::from_utf8_lossy(&out.stdout).into_owned();
+            match previous {
+                Some(p) if *p != answer => {
+                    print!(
+                        "day{day:02} part {part} changed:\n{}",
+                        watch::diff(p, &answer)
+                    )
+                }
+                _ => print!("day{day:02} part {part}: {answer}"),
+            }
+            *previous = Some(answer);
+        }
+
+        println!("watching day{day:02} for changes...");
+        while !watcher.changed() {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+// Runs this tool through cargo, from the sources as they are now.
+fn aoc(args: &[&str]) -> Command {
+    let mut cmd = Command::new(env::var_os("CARGO").unwrap_or("cargo".into()));
+    cmd.args(["run", "--quiet", "--release", "--bin", "aoc", "--"])
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"));
+    cmd
+}
@@ -0,0 +1,152 @@
+//! Read-only memory maps of input files, so that the solvers of inputs in memory read large inputs
+//! straight from the page cache instead of copying them into memory first.
+//!
+//! The standard library does not map files, hence the calls to the C library on 64-bit Unix. Other
+//! platforms read the file into memory instead, as do pipes and the other files which are not
+//! regular, since their length is unknown.
+
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+
+pub struct Mmap {
+    #[cfg(all(unix, target_pointer_width = "64"))]
+    ptr: *const u8,
+    #[cfg(all(unix, target_pointer_width = "64"))]
+    len: usize, // of the mapping, 0 if the file was read instead
+    data: Vec<u8>, // content of the file, if it was read instead of mapped
+}
+
+// The mapping is read-only and owned by this value.
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+#[cfg(all(unix, target_pointer_width = "64"))]
+mod sys {
+    use std::ffi::c_void;
+
+    pub const PROT_READ: i32 = 1;
+    pub const MAP_PRIVATE: i32 = 2;
+    pub const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+
+    extern "C" {
+        pub fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: i32,
+            flags: i32,
+            fd: i32,
+            offset: i64,
+        ) -> *mut c_void;
+        pub fn munmap(addr: *mut c_void, len: usize) -> i32;
+    }
+}
+
+impl Mmap {
+    /// Maps the whole file. Its content must not change while it is mapped, which holds for the
+    /// input files this is used with.
+    #[cfg(all(unix, target_pointer_width = "64"))]
+    pub fn map(file: &File) -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        // Files which are not regular report no length, and empty mappings are invalid.
+        let metadata = file.metadata()?;
+        if !metadata.is_file() || metadata.len() == 0 {
+            return Self::read(file);
+        }
+        let len = usize::try_from(metadata.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "file too large to map"))?;
+        // SAFETY: a new private read-only mapping of a valid descriptor, whose result is checked.
+        let ptr = unsafe {
+            sys::mmap(
+                std::ptr::null_mut(),
+                len,
+                sys::PROT_READ,
+                sys::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == sys::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mmap {
+            ptr: ptr.cast(),
+            len,
+            data: Vec::new(),
+        })
+    }
+
+    #[cfg(not(all(unix, target_pointer_width = "64")))]
+    pub fn map(file: &File) -> io::Result<Self> {
+        Self::read(file)
+    }
+
+    fn read(file: &File) -> io::Result<Self> {
+        let mut data = Vec::new();
+        io::Read::read_to_end(&mut &*file, &mut data)?;
+        Ok(Mmap {
+            #[cfg(all(unix, target_pointer_width = "64"))]
+            ptr: std::ptr::null(),
+            #[cfg(all(unix, target_pointer_width = "64"))]
+            len: 0,
+            data,
+        })
+    }
+}
+
+impl Deref for Mmap {
+    type Target = [u8];
+
+    #[cfg(all(unix, target_pointer_width = "64"))]
+    fn deref(&self) -> &[u8] {
+        if self.len == 0 {
+            return &self.data;
+        }
+        // SAFETY: the mapping is valid for `len` bytes until dropped.
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    #[cfg(not(all(unix, target_pointer_width = "64")))]
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+#[cfg(all(unix, target_pointer_width = "64"))]
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: unmaps the mapping created in `map`, which is no longer borrowed.
+            unsafe { sys::munmap(self.ptr as *mut _, self.len) };
+        }
+    }
+}
+
+#[test]
+fn same_bytes_as_read() {
+    let path = std::env::temp_dir().join(format!("aoc-mmap-{}", std::process::id()));
+    for content in [&b""[..], b"seeds: 79 14\n"] {
+        std::fs::write(&path, content).unwrap();
+        let map = Mmap::map(&File::open(&path).unwrap()).unwrap();
+        assert_eq!(&map[..], content);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn pipe_is_read() {
+    use std::os::fd::OwnedFd;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new("echo")
+        .arg("seeds: 79 14")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let pipe = File::from(OwnedFd::from(child.stdout.take().unwrap()));
+    let map = Mmap::map(&pipe).unwrap();
+    assert_eq!(&map[..], b"seeds: 79 14\n");
+    child.wait().unwrap();
+}
@@ -0,0 +1,251 @@
+//! Reports of solver runs, for humans or for tools consuming them.
+
+use crate::json::Value;
+use crate::registry::{Run, Solver};
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json, // one object per line
+    Csv,  // with a header line
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format {s:?}, expected json, csv or text")),
+        }
+    }
+}
+
+/// Result of one solver on one input.
+pub struct Record<'a> {
+    pub solver: &'a Solver,
+    pub run: Run,
+    pub input_hash: u64,
+}
+
+/// Solver which returned an error or panicked on its input, reported in place of its record.
+pub struct Failure<'a> {
+    pub solver: &'a Solver,
+    pub status: &'static str, // "error" or "panic"
+    pub message: String,
+}
+
+/// FNV-1a hash of an input, to tell which one an answer was computed from.
+pub fn hash(input: &[u8]) -> u64 {
+    fnv(0xcbf29ce484222325, input)
+}
+
+fn fnv(h: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(h, |h, &b| (h ^ u64::from(b)).wrapping_mul(0x100000001b3))
+}
+
+/// Reader hashing the bytes read through it like [`hash`], for inputs which are streamed instead
+/// of held in memory.
+pub struct Hashing<R> {
+    inner: R,
+    hash: u64,
+}
+
+impl<R: io::Read> Hashing<R> {
+    pub fn new(inner: R) -> Self {
+        Hashing {
+            inner,
+            hash: hash(&[]),
+        }
+    }
+
+    /// Hash of the whole input, reading what the solver left unread.
+    pub fn finish(mut self) -> io::Result<u64> {
+        io::copy(&mut self, &mut io::sink())?;
+        Ok(self.hash)
+    }
+}
+
+impl<R: io::Read> io::Read for Hashing<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hash = fnv(self.hash, &buf[..n]);
+        Ok(n)
+    }
+}
+
+pub fn header(w: &mut impl Write, format: Format) -> io::Result<()> {
+    match format {
+        Format::Csv => writeln!(
+            w,
+            "day,part,answer,answer_type,parse_ns,solve_ns,input_hash,\
+             allocations,allocated_bytes,peak_bytes,status,error"
+        ),
+        Format::Text | Format::Json => Ok(()),
+    }
+}
+
+pub fn write(w: &mut impl Write, format: Format, r: &Record) -> io::Result<()> {
+    let (s, run) = (r.solver, &r.run);
+    match format {
+        Format::Text => {
+            write!(
+                w,
+                "day{:02} part {}: {} ({}, parse {:?}, solve {:?}, input {:016x}",
+                s.day,
+                s.part,
+                run.answer,
+                s.answer_type(),
+                run.parse,
+                run.solve,
+                r.input_hash
+            )?;
+            if let Some(m) = run.memory {
+                write!(
+                    w,
+                    ", {} allocations of {} bytes, peak {} bytes",
+                    m.allocations, m.bytes, m.peak
+                )?;
+            }
+            writeln!(w, ")")
+        }
+        Format::Json => {
+            let n = |n: f64| Value::Number(n);
+            let record = Value::Object(vec![
+                ("day".to_string(), n(s.day.into())),
+                ("part".to_string(), n(s.part.into())),
+                ("answer".to_string(), Value::String(run.answer.clone())),
+                (
+                    "answer_type".to_string(),
+                    Value::String(s.answer_type().to_string()),
+                ),
+                ("parse_ns".to_string(), n(run.parse.as_nanos() as f64)),
+                ("solve_ns".to_string(), n(run.solve.as_nanos() as f64)),
+                (
+                    "input_hash".to_string(),
+                    Value::String(format!("{:016x}", r.input_hash)),
+                ),
+                (
+                    "memory".to_string(),
+                    run.memory.map_or(Value::Null, |m| {
+                        Value::Object(vec![
+                            ("allocations".to_string(), n(m.allocations as f64)),
+                            ("allocated_bytes".to_string(), n(m.bytes as f64)),
+                            ("peak_bytes".to_string(), n(m.peak as f64)),
+                        ])
+                    }),
+                ),
+                ("status".to_string(), Value::String("ok".to_string())),
+                ("error".to_string(), Value::Null),
+            ]);
+            writeln!(w, "{record}")
+        }
+        Format::Csv => {
+            write!(
+                w,
+                "{},{},{},{},{},{},{:016x},",
+                s.day,
+                s.part,
+                Csv(&run.answer),
+                s.answer_type(),
+                run.parse.as_nanos(),
+                run.solve.as_nanos(),
+                r.input_hash
+            )?;
+            match run.memory {
+                Some(m) => writeln!(w, "{},{},{},ok,", m.allocations, m.bytes, m.peak),
+                None => writeln!(w, ",,,ok,"),
+            }
+        }
+    }
+}
+
+/// Writes the row of a solver which failed, with the same fields as [`write`] left empty.
+pub fn write_failure(w: &mut impl Write, format: Format, f: &Failure) -> io::Result<()> {
+    let s = f.solver;
+    match format {
+        Format::Text => writeln!(
+            w,
+            "day{:02} part {}: {}: {}",
+            s.day, s.part, f.status, f.message
+        ),
+        Format::Json => {
+            let record = Value::Object(vec![
+                ("day".to_string(), Value::Number(s.day.into())),
+                ("part".to_string(), Value::Number(s.part.into())),
+                ("answer".to_string(), Value::Null),
+                (
+                    "answer_type".to_string(),
+                    Value::String(s.answer_type().to_string()),
+                ),
+                ("parse_ns".to_string(), Value::Null),
+                ("solve_ns".to_string(), Value::Null),
+                ("input_hash".to_string(), Value::Null),
+                ("memory".to_string(), Value::Null),
+                ("status".to_string(), Value::String(f.status.to_string())),
+                ("error".to_string(), Value::String(f.message.clone())),
+            ]);
+            writeln!(w, "{record}")
+        }
+        Format::Csv => writeln!(
+            w,
+            "{},{},,{},,,,,,,{},{}",
+            s.day,
+            s.part,
+            s.answer_type(),
+            f.status,
+            Csv(&f.message)
+        ),
+    }
+}
+
+// Field quoted only when needed, as answers are mostly numbers.
+struct Csv<'a>(&'a str);
+
+impl fmt::Display for Csv<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.contains([',', '"', '\n', '\r']) {
+            write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+#[test]
+fn csv_record() {
+    use std::time::Duration;
+
+    let solver = crate::registry::find(6, 1).unwrap();
+    let run = Run {
+        answer: "a,\"b\"".to_string(),
+        parse: Duration::from_nanos(12),
+        solve: Duration::from_nanos(345),
+        memory: None,
+    };
+    let mut out = Vec::new();
+    let record = Record {
+        solver,
+        run,
+        input_hash: hash(b""),
+    };
+    write(&mut out, Format::Csv, &record).unwrap();
+    let failure = Failure {
+        solver,
+        status: "error",
+        message: "no answer: overflow, again".to_string(),
+    };
+    write_failure(&mut out, Format::Csv, &failure).unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "6,1,\"a,\"\"b\"\"\",u32,12,345,cbf29ce484222325,,,,ok,\n\
+         6,1,,u32,,,,,,,error,\"no answer: overflow, again\"\n"
+    );
+}
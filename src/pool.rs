@@ -0,0 +1,79 @@
+//! Fixed-size thread pool running independent jobs, where a panicking job does not abort the
+//! others.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+thread_local! {
+    // Message of the last panic on this thread, recorded by the hook set by quiet_panics().
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Replaces the printing of panics to stderr by their recording, so that [`map`] can report them
+/// with their location instead.
+pub fn quiet_panics() {
+    panic::set_hook(Box::new(|info| {
+        LAST_PANIC.with(|p| *p.borrow_mut() = Some(info.to_string()));
+    }));
+}
+
+/// Applies `f` to every item on up to `jobs` threads, and returns the results in the order of the
+/// items. A panic in `f` is returned as an error holding its message.
+pub fn map<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<Result<R, String>> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<_>>());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                let r = panic::catch_unwind(AssertUnwindSafe(|| f(item))).map_err(message);
+                results.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(r);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|r| r.expect("every item is processed"))
+        .collect()
+}
+
+pub(crate) fn message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(m) = LAST_PANIC.with(|p| p.borrow_mut().take()) {
+        return m;
+    }
+    match payload.downcast::<String>() {
+        Ok(s) => *s,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(s) => s.to_string(),
+            Err(_) => "panicked".to_string(),
+        },
+    }
+}
+
+#[test]
+fn isolate_panics() {
+    let results = map(&[1, 2, 3, 4], 2, |&n| {
+        if n == 3 {
+            panic!("three");
+        }
+        n * 10
+    });
+    assert_eq!(results[..2], [Ok(10), Ok(20)]);
+    assert!(results[2].as_ref().is_err_and(|e| e.contains("three")));
+    assert_eq!(results[3], Ok(40));
+}
@@ -0,0 +1,142 @@
+use crate::day01::Day01;
+use crate::day02::Day02;
+use crate::day03::Day03;
+use crate::day04::Day04;
+use crate::day05::Day05;
+use crate::day06::Day06;
+use crate::day07::Day07;
+use crate::day08::Day08;
+use crate::{alloc, Error, Solution};
+use std::fmt::Display;
+use std::io;
+use std::time::{Duration, Instant};
+
+/// Type-erased entry point to one part of a day's solution.
+pub struct Solver {
+    pub day: u8,
+    pub part: u8,
+    pub name: &'static str, // function implementing the part in dayNN/exN.rs
+    answer_type: fn() -> &'static str,
+    run: fn(&mut dyn io::Read) -> Result<Run, Error>,
+    stream: fn(&mut dyn io::Read) -> Result<Run, Error>,
+    bytes: fn(&[u8]) -> Result<Run, Error>,
+}
+
+/// Answer of a solver, with the time spent parsing the input and solving the puzzle.
+pub struct Run {
+    pub answer: String,
+    pub parse: Duration,
+    pub solve: Duration,
+    pub memory: Option<alloc::Stats>, // when allocations are counted
+}
+
+impl Solver {
+    const fn part1<S: Solution>(name: &'static str) -> Self {
+        Solver {
+            day: S::DAY,
+            part: 1,
+            name,
+            answer_type: std::any::type_name::<S::Answer1>,
+            run: |r| measure(|| S::parse(r), S::part1),
+            stream: |r| measure(|| Ok(()), |()| S::stream1(r)),
+            bytes: |input| measure(|| Ok(()), |()| S::part1_bytes(input)),
+        }
+    }
+
+    const fn part2<S: Solution>(name: &'static str) -> Self {
+        Solver {
+            day: S::DAY,
+            part: 2,
+            name,
+            answer_type: std::any::type_name::<S::Answer2>,
+            run: |r| measure(|| S::parse(r), S::part2),
+            stream: |r| measure(|| Ok(()), |()| S::stream2(r)),
+            bytes: |input| measure(|| Ok(()), |()| S::part2_bytes(input)),
+        }
+    }
+
+    /// Name of the Rust type of the answer, e.g. `u32`.
+    pub fn answer_type(&self) -> &'static str {
+        (self.answer_type)()
+    }
+
+    pub fn solve(&self, r: &mut dyn io::Read) -> Result<Run, Error> {
+        (self.run)(r)
+    }
+
+    /// Solves straight from the reader, without holding the whole input in memory first. The time
+    /// spent reading the input then counts as solving time.
+    pub fn stream(&self, r: &mut dyn io::Read) -> Result<Run, Error> {
+        (self.stream)(r)
+    }
+
+    /// Solves from the whole input in memory, whose lines are split in place. Parsing then counts
+    /// as solving time.
+    pub fn solve_bytes(&self, input: &[u8]) -> Result<Run, Error> {
+        (self.bytes)(input)
+    }
+}
+
+// Times the parsing and the solving separately, and counts their allocations when enabled.
+fn measure<I, A: Display>(
+    parse: impl FnOnce() -> Result<I, Error>,
+    solve: impl FnOnce(&I) -> Result<A, Error>,
+) -> Result<Run, Error> {
+    let (result, memory) = alloc::measure(|| -> Result<_, Error> {
+        let begin = Instant::now();
+        let input = parse()?;
+        let parsed = Instant::now();
+        let answer = solve(&input)?;
+        Ok((answer, parsed - begin, Instant::now() - parsed))
+    });
+    let (answer, parse, solve) = result?;
+    Ok(Run {
+        answer: answer.to_string(),
+        parse,
+        solve,
+        memory,
+    })
+}
+
+pub static SOLVERS: [Solver; 16] = [
+    Solver::part1::<Day01>("sum"),
+    Solver::part2::<Day01>("sum"),
+    Solver::part1::<Day02>("sum"),
+    Solver::part2::<Day02>("sum"),
+    Solver::part1::<Day03>("sum"),
+    Solver::part2::<Day03>("sum"),
+    Solver::part1::<Day04>("score"),
+    Solver::part2::<Day04>("cards"),
+    Solver::part1::<Day05>("lowest"),
+    Solver::part2::<Day05>("lowest"),
+    Solver::part1::<Day06>("product"),
+    Solver::part2::<Day06>("ways"),
+    Solver::part1::<Day07>("total"),
+    Solver::part2::<Day07>("total"),
+    Solver::part1::<Day08>("steps"),
+    Solver::part2::<Day08>("steps"),
+];
+
+pub fn find(day: u8, part: u8) -> Option<&'static Solver> {
+    SOLVERS.iter().find(|s| s.day == day && s.part == part)
+}
+
+#[cfg(feature = "count-allocations")]
+#[test]
+fn streaming_in_constant_memory() {
+    // Days whose solutions only keep a few lines at once.
+    for s in SOLVERS.iter().filter(|s| s.day <= 4) {
+        let peak = |size| {
+            let input = crate::gen::generate(s.day, size, 1).unwrap().input;
+            let run = s.stream(&mut input.as_bytes()).unwrap();
+            run.memory.unwrap().peak
+        };
+        let (small, large) = (peak(1000), peak(20_000));
+        assert!(
+            large <= small + 1024,
+            "day {} part {}: peak of {small} bytes for 1000 lines, {large} for 20000",
+            s.day,
+            s.part
+        );
+    }
+}
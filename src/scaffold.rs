@@ -0,0 +1,313 @@
+//! Generation of a new day from the templates in `templates`, registered everywhere the existing
+//! days are.
+
+use crate::examples;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const EX1_TEMPLATE: &str = include_str!("../templates/day/ex1.rs");
+const EX2_TEMPLATE: &str = include_str!("../templates/day/ex2.rs");
+const MOD_TEMPLATE: &str = include_str!("../templates/day/mod.rs");
+const FUZZ_TEMPLATE: &str = include_str!("../templates/fuzz/day.rs");
+
+/// Creates `dayNN/{mod,ex1,ex2}.rs`, an empty `dayNN/examples` directory and the fuzz target
+/// `fuzz/fuzz_targets/dayNN.rs` in the repository at `root`, and registers the day in Cargo.toml,
+/// fuzz/Cargo.toml, src/lib.rs and src/registry.rs.
+pub fn new_day(root: &Path, day: u8) -> io::Result<()> {
+    let nn = format!("{day:02}");
+    let dir = root.join(format!("day{nn}"));
+    if dir.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", dir.display()),
+        ));
+    }
+
+    // All the registrations are computed before writing anything, so that a failure leaves the
+    // repository untouched.
+    let edits = [
+        (
+            root.join("fuzz").join("Cargo.toml"),
+            vec![format!(
+                "  {{ name = \"day{nn}\", path = \"fuzz_targets/day{nn}.rs\", \
+                 test = false, doc = false, bench = false }},"
+            )],
+            Some("bin = ["),
+            "  { name = \"day",
+        ),
+        (
+            root.join("Cargo.toml"),
+            vec![
+                format!("  {{ name = \"day{nn}-1\", path = \"day{nn}/ex1.rs\" }},"),
+                format!("  {{ name = \"day{nn}-2\", path = \"day{nn}/ex2.rs\" }},"),
+            ],
+            Some("bin = ["),
+            "  { name = \"day",
+        ),
+        (
+            root.join("src").join("lib.rs"),
+            vec![
+                format!("#[path = \"../day{nn}/mod.rs\"]"),
+                format!("pub mod day{nn};"),
+            ],
+            None,
+            "#[path = \"../day",
+        ),
+        (
+            root.join("src").join("registry.rs"),
+            vec![format!("use crate::day{nn}::Day{nn};")],
+            None,
+            "use crate::day",
+        ),
+        (
+            root.join("src").join("registry.rs"),
+            vec![
+                format!("    Solver::part1::<Day{nn}>(\"solve\"),"),
+                format!("    Solver::part2::<Day{nn}>(\"solve\"),"),
+            ],
+            None,
+            "    Solver::part",
+        ),
+    ];
+    let mut files: Vec<(&Path, String)> = Vec::with_capacity(4);
+    for (path, lines, array, prefix) in &edits {
+        let text = match files.iter_mut().find(|f| f.0 == path) {
+            Some(f) => &mut f.1,
+            None => {
+                files.push((path, fs::read_to_string(path)?));
+                &mut files.last_mut().expect("just pushed").1
+            }
+        };
+        *text = insert_sorted(text, *array, prefix, lines, day).ok_or_else(|| {
+            let place = match array {
+                Some(array) => format!("no {array:?} array"),
+                None => format!("no line starting with {prefix:?}"),
+            };
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{place} in {}", path.display()),
+            )
+        })?;
+    }
+    let registry = &mut files.last_mut().expect("registry.rs is edited").1;
+    *registry = grow_solvers(registry).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "no SOLVERS array in registry.rs",
+        )
+    })?;
+
+    let fill = |template: &str| {
+        template
+            .replace("{{NN}}", &nn)
+            .replace("{{N}}", &day.to_string())
+    };
+    fs::create_dir_all(dir.join(examples::DIR))?;
+    fs::write(dir.join("mod.rs"), fill(MOD_TEMPLATE))?;
+    fs::write(dir.join("ex1.rs"), fill(EX1_TEMPLATE))?;
+    fs::write(dir.join("ex2.rs"), fill(EX2_TEMPLATE))?;
+    fs::write(
+        root.join("fuzz")
+            .join("fuzz_targets")
+            .join(format!("day{nn}.rs")),
+        fill(FUZZ_TEMPLATE),
+    )?;
+    for (path, text) in files {
+        fs::write(path, text)?;
+    }
+    Ok(())
+}
+
+/// Inserts `lines` among the lines starting with `prefix`, which mention their day number, keeping
+/// them ordered by day. The inserted group is kept whole, e.g. an attribute and its item.
+///
+/// With `array`, the line opening a TOML array such as `bin = [`, only the lines of that array are
+/// considered, and `lines` go before its closing bracket if none of them starts with `prefix`.
+fn insert_sorted(
+    text: &str,
+    array: Option<&str>,
+    prefix: &str,
+    lines: &[String],
+    day: u8,
+) -> Option<String> {
+    let mut out = text.lines().map(str::to_string).collect::<Vec<_>>();
+    let (begin, end) = match array {
+        Some(array) => {
+            let begin = out.iter().position(|l| l.trim_end() == array)? + 1;
+            (
+                begin,
+                begin + out[begin..].iter().position(|l| l.trim() == "]")?,
+            )
+        }
+        None => (0, out.len()),
+    };
+    let matching = out[begin..end]
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.starts_with(prefix))
+        .map(|(i, l)| (begin + i, day_number(l)))
+        .collect::<Vec<_>>();
+    // Lines in each existing group, assumed to be shaped like the inserted one.
+    let group = lines.len()
+        / lines
+            .iter()
+            .filter(|l| l.starts_with(prefix))
+            .count()
+            .max(1);
+    let at = match matching.iter().rfind(|(_, d)| *d < Some(day)) {
+        Some((i, _)) => i + group,
+        None => match matching.first() {
+            Some(&(i, _)) => i,
+            None => array.map(|_| end)?,
+        },
+    };
+    out.splice(at..at, lines.iter().cloned());
+    Some(out.join("\n") + "\n")
+}
+
+// First number following "day" or "Day" in the line.
+fn day_number(line: &str) -> Option<u8> {
+    let i = line.find("day").or_else(|| line.find("Day"))?;
+    let digits = line[i + 3..].chars().take_while(char::is_ascii_digit);
+    digits.collect::<String>().parse().ok()
+}
+
+fn grow_solvers(text: &str) -> Option<String> {
+    let start = text.find("[Solver; ")? + "[Solver; ".len();
+    let len = text[start..].find(']')?;
+    let n = text[start..start + len].parse::<usize>().ok()?;
+    Some(format!(
+        "{}{}{}",
+        &text[..start],
+        n + 2,
+        &text[start + len..]
+    ))
+}
+
+#[test]
+fn register_new_day() {
+    let registry = "\
+        use crate::day01::Day01;\n\
+        use crate::day03::Day03;\n\
+        \n\
+        pub static SOLVERS: [Solver; 4] = [\n    \
+            Solver::part1::<Day01>(\"sum\"),\n    \
+            Solver::part2::<Day01>(\"sum\"),\n    \
+            Solver::part1::<Day03>(\"sum\"),\n    \
+            Solver::part2::<Day03>(\"sum\"),\n\
+        ];\n";
+    let registry = insert_sorted(
+        registry,
+        None,
+        "use crate::day",
+        &["use crate::day02::Day02;".to_string()],
+        2,
+    )
+    .unwrap();
+    let registry = insert_sorted(
+        &registry,
+        None,
+        "    Solver::part",
+        &[
+            "    Solver::part1::<Day02>(\"solve\"),".to_string(),
+            "    Solver::part2::<Day02>(\"solve\"),".to_string(),
+        ],
+        2,
+    )
+    .unwrap();
+    assert_eq!(
+        grow_solvers(&registry).unwrap(),
+        "\
+        use crate::day01::Day01;\n\
+        use crate::day02::Day02;\n\
+        use crate::day03::Day03;\n\
+        \n\
+        pub static SOLVERS: [Solver; 6] = [\n    \
+            Solver::part1::<Day01>(\"sum\"),\n    \
+            Solver::part2::<Day01>(\"sum\"),\n    \
+            Solver::part1::<Day02>(\"solve\"),\n    \
+            Solver::part2::<Day02>(\"solve\"),\n    \
+            Solver::part1::<Day03>(\"sum\"),\n    \
+            Solver::part2::<Day03>(\"sum\"),\n\
+        ];\n"
+    );
+}
+
+#[test]
+fn scaffold_into_manifests() {
+    use crate::json::{self, Value};
+    use std::process::Command;
+
+    // Kind and name of every target of a manifest, as cargo reads it.
+    let targets = |manifest: &Path| {
+        let out = Command::new(env!("CARGO"))
+            .args([
+                "metadata",
+                "--no-deps",
+                "--offline",
+                "--format-version",
+                "1",
+            ])
+            .arg("--manifest-path")
+            .arg(manifest)
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let metadata = json::parse(&String::from_utf8(out.stdout).unwrap()).unwrap();
+        let Some(Value::Array(packages)) = metadata.get("packages") else {
+            panic!("no packages in the metadata of {}", manifest.display());
+        };
+        let Some(Value::Array(targets)) = packages[0].get("targets") else {
+            panic!("no targets in the metadata of {}", manifest.display());
+        };
+        let mut targets = targets
+            .iter()
+            .map(|t| {
+                let kind = match t.get("kind") {
+                    Some(Value::Array(kinds)) => kinds[0].as_str().unwrap().to_string(),
+                    _ => panic!("target without kind"),
+                };
+                (kind, t.get("name").unwrap().as_str().unwrap().to_string())
+            })
+            .collect::<Vec<_>>();
+        targets.sort();
+        targets
+    };
+
+    let repo = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+    for file in [
+        "Cargo.toml",
+        "fuzz/Cargo.toml",
+        "src/lib.rs",
+        "src/registry.rs",
+    ] {
+        fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+        fs::copy(repo.join(file), root.join(file)).unwrap();
+    }
+    fs::create_dir_all(root.join("fuzz").join("fuzz_targets")).unwrap();
+    let day = (1..=25)
+        .find(|&d| crate::registry::SOLVERS.iter().all(|s| s.day != d))
+        .unwrap();
+    new_day(&root, day).unwrap();
+
+    let nn = format!("{day:02}");
+    for (manifest, added) in [
+        (
+            "Cargo.toml",
+            vec![format!("day{nn}-1"), format!("day{nn}-2")],
+        ),
+        ("fuzz/Cargo.toml", vec![format!("day{nn}")]),
+    ] {
+        let mut expected = targets(&repo.join(manifest));
+        expected.extend(added.into_iter().map(|name| ("bin".to_string(), name)));
+        expected.sort();
+        assert_eq!(targets(&root.join(manifest)), expected, "{manifest}");
+    }
+    fs::remove_dir_all(&root).unwrap();
+}
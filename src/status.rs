@@ -0,0 +1,160 @@
+//! Progress over the 25 days of the calendar, derived from the registered solvers, their examples,
+//! the recorded answers and the bench baseline.
+
+use crate::answers::{Answer, Outcome};
+use crate::bench::Baseline;
+use crate::examples;
+use crate::registry::SOLVERS;
+use std::fmt::Write;
+use std::time::Duration;
+
+pub const DAYS: u8 = 25;
+
+#[derive(Default)]
+pub struct Part {
+    pub exists: bool,
+    pub examples: Option<bool>,   // whether they all pass, if there is any
+    pub answer: bool,             // a verified answer is recorded
+    pub timing: Option<Duration>, // median parse and solve time of the baseline, through io::Read
+}
+
+/// Status of both parts of every day of the calendar. Examples are run, but not the inputs, whose
+/// answers `aoc verify` checks.
+pub fn collect(answers: &[Answer], baseline: Option<&Baseline>) -> Vec<[Part; 2]> {
+    let mut days = (1..=DAYS)
+        .map(|_| <[Part; 2]>::default())
+        .collect::<Vec<_>>();
+    for s in &SOLVERS {
+        let part = &mut days[usize::from(s.day) - 1][usize::from(s.part) - 1];
+        part.exists = true;
+        part.answer = answers.iter().any(|a| a.day == s.day && a.part == s.part);
+        part.timing = baseline
+            .and_then(|b| b.get(s.day, s.part, false))
+            .map(|(parse, solve)| parse + solve);
+        // Examples which cannot be loaded count as failing.
+        part.examples = match examples::load(s.day) {
+            Ok(examples) => {
+                let mut outcomes = examples
+                    .iter()
+                    .filter(|a| a.part == s.part)
+                    .map(|a| matches!(a.check(s), Outcome::Pass))
+                    .peekable();
+                outcomes.peek().is_some().then(|| outcomes.all(|pass| pass))
+            }
+            Err(_) => Some(false),
+        };
+    }
+    days
+}
+
+const COLUMNS: usize = 5;
+const CELL_WIDTH: usize = 13;
+
+/// Renders the days as a calendar of 5 weeks of 5 days, with a legend.
+pub fn render(days: &[[Part; 2]]) -> String {
+    let mut out = String::new();
+    let border = format!(
+        "+{}\n",
+        format!("{}+", "-".repeat(CELL_WIDTH)).repeat(COLUMNS)
+    );
+    for (w, week) in days.chunks(COLUMNS).enumerate() {
+        out += &border;
+        // One line for the day and its stars, then one per part.
+        let mut lines = [String::from("|"), String::from("|"), String::from("|")];
+        for (d, day) in week.iter().enumerate() {
+            let stars = day
+                .iter()
+                .map(|p| if p.exists && p.answer { '*' } else { ' ' })
+                .collect::<String>();
+            let cells = [
+                format!("{:>2}  {stars}", w * COLUMNS + d + 1),
+                part_cell(1, &day[0]),
+                part_cell(2, &day[1]),
+            ];
+            for (line, cell) in lines.iter_mut().zip(cells) {
+                let pad = CELL_WIDTH.saturating_sub(cell.chars().count() + 1);
+                let _ = write!(line, " {cell}{}|", " ".repeat(pad));
+            }
+        }
+        for line in lines {
+            out += &line;
+            out.push('\n');
+        }
+    }
+    out += &border;
+
+    let count = |f: fn(&Part) -> bool| days.iter().flatten().filter(|&p| f(p)).count();
+    let _ = writeln!(
+        out,
+        "{} parts solved, {} stars (verified answers), {} with passing examples",
+        count(|p| p.exists),
+        count(|p| p.exists && p.answer),
+        count(|p| p.examples == Some(true)),
+    );
+    out +=
+        "examples: ok pass, FAIL fail, -- none; time: median parse + solve of the bench baseline\n";
+    out
+}
+
+fn part_cell(n: u8, p: &Part) -> String {
+    if !p.exists {
+        return String::new();
+    }
+    let examples = match p.examples {
+        Some(true) => "ok",
+        Some(false) => "FAIL",
+        None => "--",
+    };
+    match p.timing {
+        Some(t) => format!("{n} {examples} {}", short(t)),
+        None => format!("{n} {examples}"),
+    }
+}
+
+// Duration with 3 significant digits at most, so that it fits in a cell.
+fn short(d: Duration) -> String {
+    let (value, unit) = match d.as_nanos() {
+        n if n < 1_000 => return format!("{n}ns"),
+        n if n < 1_000_000 => (n as f64 / 1e3, "µs"),
+        n if n < 1_000_000_000 => (n as f64 / 1e6, "ms"),
+        n => (n as f64 / 1e9, "s"),
+    };
+    if value < 10.0 {
+        format!("{value:.1}{unit}")
+    } else {
+        format!("{value:.0}{unit}")
+    }
+}
+
+#[test]
+fn calendar() {
+    let mut days = (1..=DAYS)
+        .map(|_| <[Part; 2]>::default())
+        .collect::<Vec<_>>();
+    days[0] = [
+        Part {
+            exists: true,
+            examples: Some(true),
+            answer: true,
+            timing: Some(Duration::from_micros(1500)),
+        },
+        Part {
+            exists: true,
+            examples: Some(false),
+            answer: false,
+            timing: None,
+        },
+    ];
+    let out = render(&days);
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[1..4],
+        [
+            "|  1  *       |  2          |  3          |  4          |  5          |",
+            "| 1 ok 1.5ms  |             |             |             |             |",
+            "| 2 FAIL      |             |             |             |             |",
+        ]
+    );
+    assert!(lines[17].ends_with("| 25          |"));
+    assert!(out.contains("2 parts solved, 1 stars"));
+}
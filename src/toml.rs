@@ -0,0 +1,354 @@
+//! Minimal TOML support for the configuration files of the `aoc` tool, so that the crate keeps
+//! depending only on std.
+//!
+//! Only the subset we write by hand is supported: comments, `[table]` and `[[array]]` headers with
+//! dotted or quoted keys, and `key = value` pairs whose value is a string, an integer or a boolean.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Bool(bool),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "{s}"),
+            Value::Integer(n) => write!(f, "{n}"),
+            Value::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+/// Key/value pairs found under a header, or before the first one for the root table.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Table {
+    pub name: String, // dotted keys joined by '.', empty for the root table
+    pub array: bool,  // declared with `[[name]]`
+    pub line: usize,  // 1-based line of the header
+    pub entries: Vec<(String, Value)>,
+}
+
+impl Table {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().find(|e| e.0 == key).map(|e| &e.1)
+    }
+}
+
+/// Error returned when parsing malformed TOML, with the 1-based line at which it was detected.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub expected: &'static str,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "malformed TOML at line {}: expected {}",
+            self.line, self.expected
+        )
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Parses a document into its tables, in order of appearance. The root table always comes first.
+pub fn parse(s: &str) -> Result<Vec<Table>, Error> {
+    let mut tables = vec![Table {
+        name: String::new(),
+        array: false,
+        line: 1,
+        entries: Vec::new(),
+    }];
+
+    for (i, l) in s.lines().enumerate() {
+        let err = |expected| Error {
+            line: i + 1,
+            expected,
+        };
+        let mut p = Parser(l.trim());
+        if p.comment_or_end() {
+            continue;
+        }
+
+        if p.eat('[') {
+            let array = p.eat('[');
+            let name = p.key().ok_or(err("table name"))?;
+            if !p.eat(']') || (array && !p.eat(']')) {
+                return Err(err("']'"));
+            }
+            if !p.comment_or_end() {
+                return Err(err("end of line"));
+            }
+            if !array && tables.iter().any(|t| t.name == name) {
+                return Err(err("unique table name"));
+            }
+            tables.push(Table {
+                name,
+                array,
+                line: i + 1,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+
+        let key = p.key().ok_or(err("key"))?;
+        if !p.eat('=') {
+            return Err(err("'='"));
+        }
+        let value = p.value().ok_or(err("string, integer or boolean"))?;
+        if !p.comment_or_end() {
+            return Err(err("end of line"));
+        }
+        let table = tables.last_mut().expect("the root table is never removed");
+        if table.get(&key).is_some() {
+            return Err(err("unique key"));
+        }
+        table.entries.push((key, value));
+    }
+    Ok(tables)
+}
+
+struct Parser<'a>(&'a str);
+
+impl Parser<'_> {
+    fn eat(&mut self, c: char) -> bool {
+        self.0 = self.0.trim_start();
+        match self.0.strip_prefix(c) {
+            Some(rest) => {
+                self.0 = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn comment_or_end(&mut self) -> bool {
+        self.0 = self.0.trim_start();
+        self.0.is_empty() || self.0.starts_with('#')
+    }
+
+    // Dotted keys made of bare or quoted parts, e.g. `day05."alt input"`.
+    fn key(&mut self) -> Option<String> {
+        let mut key = String::new();
+        loop {
+            self.0 = self.0.trim_start();
+            if self.0.starts_with('"') {
+                key.push_str(&self.string()?);
+            } else {
+                let end = self
+                    .0
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                    .unwrap_or(self.0.len());
+                if end == 0 {
+                    return None;
+                }
+                key.push_str(&self.0[..end]);
+                self.0 = &self.0[end..];
+            }
+            if !self.eat('.') {
+                return Some(key);
+            }
+            key.push('.');
+        }
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.0 = self.0.trim_start();
+        if self.0.starts_with('"') {
+            return self.string().map(Value::String);
+        }
+        let end = self
+            .0
+            .find(|c: char| c.is_whitespace() || c == '#')
+            .unwrap_or(self.0.len());
+        let value = match &self.0[..end] {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            n => Value::Integer(n.replace('_', "").parse().ok()?),
+        };
+        self.0 = &self.0[end..];
+        Some(value)
+    }
+
+    // Basic string, with the usual escapes.
+    fn string(&mut self) -> Option<String> {
+        let mut chars = self.0.strip_prefix('"')?.char_indices();
+        let mut s = String::new();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.0 = &self.0[i + 2..];
+                    return Some(s);
+                }
+                '\\' => match chars.next()?.1 {
+                    '"' => s.push('"'),
+                    '\\' => s.push('\\'),
+                    'n' => s.push('\n'),
+                    'r' => s.push('\r'),
+                    't' => s.push('\t'),
+                    'u' => {
+                        let hex = (0..4).filter_map(|_| chars.next()).map(|(_, c)| c);
+                        let n = u32::from_str_radix(&hex.collect::<String>(), 16).ok()?;
+                        s.push(char::from_u32(n)?);
+                    }
+                    _ => return None,
+                },
+                c => s.push(c),
+            }
+        }
+        None
+    }
+}
+
+#[test]
+fn tables() {
+    let doc = "\
+        # comment\n\
+        title = \"a \\\"quoted\\\" value\"\n\
+        \n\
+        [[day05]]\n\
+        part1 = 1_000 # trailing comment\n\
+        [day05.\"alt input\"]\n\
+        ok = true\n\
+        ";
+    let tables = parse(doc).unwrap();
+    assert_eq!(tables.len(), 3);
+    assert_eq!(
+        tables[0].get("title"),
+        Some(&Value::String("a \"quoted\" value".to_string()))
+    );
+    assert!(tables[1].array);
+    assert_eq!(tables[1].get("part1"), Some(&Value::Integer(1000)));
+    assert_eq!(tables[2].name, "day05.alt input");
+    assert_eq!(tables[2].line, 6);
+    assert_eq!(
+        parse("[day05]\npart1 = 1\npart1 = 2\n"),
+        Err(Error {
+            line: 3,
+            expected: "unique key"
+        })
+    );
+}
+
+// Writes tables back as TOML, with bare keys where possible and a blank line before each header.
+#[cfg(test)]
+fn write(tables: &[Table]) -> String {
+    fn string(s: &str) -> String {
+        let mut out = String::from('"');
+        for c in s.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+        out
+    }
+    fn key(k: &str) -> String {
+        let bare = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+        if !k.is_empty() && k.chars().all(bare) {
+            k.to_string()
+        } else {
+            string(k)
+        }
+    }
+
+    let mut doc = String::new();
+    for (i, t) in tables.iter().enumerate() {
+        if i > 0 {
+            let name = t.name.split('.').map(key).collect::<Vec<_>>().join(".");
+            let (open, close) = if t.array { ("[[", "]]") } else { ("[", "]") };
+            doc.push_str(&format!("\n{open}{name}{close}\n"));
+        }
+        for (k, v) in &t.entries {
+            let v = match v {
+                Value::String(s) => string(s),
+                v => v.to_string(),
+            };
+            doc.push_str(&format!("{} = {v}\n", key(k)));
+        }
+    }
+    doc
+}
+
+#[cfg(test)]
+fn any_tables() -> impl proptest::strategy::Strategy<Value = Vec<Table>> {
+    use proptest::prelude::*;
+
+    // Quoted keys could hold dots, but table names are split on them when written.
+    let part = || {
+        prop_oneof![
+            "[a-z0-9_-]{1,8}",
+            any::<String>().prop_map(|s| s.replace('.', ""))
+        ]
+    };
+    let value = prop_oneof![
+        any::<String>().prop_map(Value::String),
+        any::<i64>().prop_map(Value::Integer),
+        any::<bool>().prop_map(Value::Bool),
+    ];
+    let entries = proptest::collection::vec((part(), value), 0..4).prop_map(|mut entries| {
+        let mut seen = std::collections::HashSet::new();
+        entries.retain(|(k, _)| seen.insert(k.clone()));
+        entries
+    });
+    let name = proptest::collection::vec(part(), 1..3).prop_map(|parts| parts.join("."));
+    let tables = proptest::collection::vec((name, any::<bool>(), entries.clone()), 0..4);
+    (entries, tables)
+        .prop_map(|(entries, tables)| {
+            let mut line = entries.len();
+            let root = Table {
+                name: String::new(),
+                array: false,
+                line: 1,
+                entries,
+            };
+            let tables = tables.into_iter().map(|(name, array, entries)| {
+                let t = Table {
+                    name,
+                    array,
+                    line: line + 2,
+                    entries,
+                };
+                line += 2 + t.entries.len();
+                t
+            });
+            std::iter::once(root).chain(tables).collect::<Vec<_>>()
+        })
+        // only `[[array]]` tables may share their name
+        .prop_filter("unique table names", |tables| {
+            tables.iter().enumerate().all(|(i, t)| {
+                tables[..i]
+                    .iter()
+                    .all(|u| u.name != t.name || (u.array && t.array))
+            })
+        })
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn parse_written(tables in any_tables()) {
+        proptest::prop_assert_eq!(parse(&write(&tables)), Ok(tables));
+    }
+}
@@ -0,0 +1,108 @@
+//! Polling of a day's files, so that its solver can be rebuilt and re-run whenever they change.
+
+use crate::examples;
+use crate::input::Source;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Files of the given day: its sources, examples and input.
+pub fn paths(day: u8) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("day{day:02}"));
+    let mut paths = vec![dir, examples::dir(day)];
+    if let Source::File(input) = Source::resolve(day, None) {
+        paths.push(input);
+    }
+    paths
+}
+
+/// Detects changes in files, or in the files directly inside directories.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    state: Vec<(PathBuf, Option<SystemTime>, u64)>, // modification time and length of each file
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let state = snapshot(&paths);
+        Watcher { paths, state }
+    }
+
+    /// Returns whether any file was created, modified or removed since the previous call.
+    pub fn changed(&mut self) -> bool {
+        let state = snapshot(&self.paths);
+        if state == self.state {
+            return false;
+        }
+        self.state = state;
+        true
+    }
+}
+
+fn snapshot(paths: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>, u64)> {
+    let mut state = Vec::new();
+    for path in paths {
+        let Ok(meta) = fs::metadata(path) else {
+            continue;
+        };
+        if !meta.is_dir() {
+            state.push((path.clone(), meta.modified().ok(), meta.len()));
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(path) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if let Ok(meta) = entry.metadata() {
+                if meta.is_file() {
+                    state.push((entry.path(), meta.modified().ok(), meta.len()));
+                }
+            }
+        }
+    }
+    state.sort();
+    state
+}
+
+/// Line-by-line difference between two answers, with removed lines prefixed by `-` and added ones
+/// by `+`. Lines which did not change are prefixed by a space.
+pub fn diff(old: &str, new: &str) -> String {
+    let (old, new) = (
+        old.lines().collect::<Vec<_>>(),
+        new.lines().collect::<Vec<_>>(),
+    );
+    let mut out = String::new();
+    for i in 0..old.len().max(new.len()) {
+        match (old.get(i), new.get(i)) {
+            (Some(o), Some(n)) if o == n => out += &format!("  {o}\n"),
+            (o, n) => {
+                if let Some(o) = o {
+                    out += &format!("- {o}\n");
+                }
+                if let Some(n) = n {
+                    out += &format!("+ {n}\n");
+                }
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn detect_changes() {
+    let dir = std::env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("input");
+    fs::write(&file, "1\n").unwrap();
+
+    let mut watcher = Watcher::new(vec![dir.clone()]);
+    assert!(!watcher.changed());
+    fs::write(&file, "12\n").unwrap();
+    assert!(watcher.changed());
+    assert!(!watcher.changed());
+    fs::remove_file(&file).unwrap();
+    assert!(watcher.changed());
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(diff("35\n", "36\n"), "- 35\n+ 36\n");
+}
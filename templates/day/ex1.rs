@@ -0,0 +1,39 @@
+use adventofcode2023::lines::{self, LineSource, Lines};
+use adventofcode2023::{input, Error, ParseError};
+use std::io;
+use std::process::ExitCode;
+
+const DAY: u8 = {{N}};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", solve(&parse(f)?)?);
+    Ok(())
+}
+
+pub fn parse<R: io::Read>(r: R) -> Result<Vec<u64>, Error> {
+    Lines::new(r).parse_each(|i, line| Ok(parse_line(i, line)?))
+}
+
+// Same as `parse`, for an input held in memory.
+pub fn parse_bytes(input: &[u8]) -> Result<Vec<u64>, Error> {
+    lines::split(input).parse_each(|i, line| Ok(parse_line(i, line)?))
+}
+
+pub fn solve(numbers: &[u64]) -> Result<u64, Error> {
+    Ok(numbers.iter().sum())
+}
+
+fn parse_line(i: usize, line: &[u8]) -> Result<u64, ParseError> {
+    lines::number(line).ok_or_else(|| ParseError::token(DAY, i, line, line.len(), line, "number"))
+}
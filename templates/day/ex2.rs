@@ -0,0 +1,25 @@
+use adventofcode2023::day{{NN}}::ex1;
+use adventofcode2023::{input, Error};
+use std::process::ExitCode;
+
+const DAY: u8 = {{N}};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let f = input::from_args(DAY)?;
+    println!("{}", solve(&ex1::parse(f)?)?);
+    Ok(())
+}
+
+pub fn solve(numbers: &[u64]) -> Result<u64, Error> {
+    Ok(numbers.iter().sum())
+}